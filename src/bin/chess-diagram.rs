@@ -0,0 +1,146 @@
+use std::path::Path;
+use std::{env, fs, process};
+
+use chess::domain::game::annotation::{parse_arrow, parse_square_marker};
+use chess::domain::game::ChessGame;
+use chess::domain::pgn::import_pgn;
+use chess::domain::pieces::PieceColor;
+use chess::export::animation::{export_gif, DEFAULT_FRAME_DELAY_MS, MAX_FRAME_DELAY_MS};
+use chess::export::raster::{export_png, PieceSprites};
+use chess::export::svg::export_svg;
+use chess::export::{DiagramOptions, MIN_BOARD_SIZE};
+
+const USAGE: &str = "Usage: chess-diagram [OPTIONS] --output <FILE>
+
+Export a diagram of a chess position to an SVG or PNG file, or an animation of all moves of a game
+to a GIF file, depending on the extension of the output file.
+
+  --output <FILE>        The SVG, PNG or GIF file to write
+  --fen <FEN>            Position in Forsyth-Edwards Notation [default: start position]
+  --pgn <FILE>           Game in Portable Game Notation, shown after its last move
+  --ply <N>              Show the position of the game after N plies instead
+  --delay <MS>           Time each move of an animation is shown, at most 60000 [default: 1000]
+  --flip                 Show Black at the bottom
+  --no-coordinates       Omit the rank numbers and file letters
+  --size <PIXELS>        Length of the edge of the board, at least 64 [default: 480]
+  --square <MARKER>      Highlight a square, e.g. Rd4 (colors: G, R, Y, B); repeatable
+  --arrow <MARKER>       Draw an arrow, e.g. Ge2e4; repeatable
+  --resource-dir <DIR>   Directory of the sprite sheet of the pieces [default: resources]
+  -h, --help             Print this help";
+
+/** Main entry point of the diagram exporter. */
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err(error) = run(&args) {
+        eprintln!("{}", error);
+        process::exit(2);
+    }
+}
+
+/** Write the diagram described by given command line arguments. */
+fn run(args: &[String]) -> Result<(), String> {
+    let mut game: Option<ChessGame> = None;
+    let mut ply: Option<usize> = None;
+    let mut frame_delay_ms = DEFAULT_FRAME_DELAY_MS;
+    let mut output: Option<String> = None;
+    let mut resource_dir = "resources".to_string();
+    let mut options = DiagramOptions::default();
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        let mut value = || {
+            remaining
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value of option '{}'", arg))
+        };
+        match arg.as_str() {
+            "--fen" => game = Some(ChessGame::from_fen(&value()?)?),
+            "--pgn" => {
+                let path = value()?;
+                let text =
+                    fs::read_to_string(&path).map_err(|error| format!("{}: {}", path, error))?;
+                game = Some(import_pgn(&text).map_err(|error| format!("{}: {}", path, error))?);
+            }
+            "--ply" => ply = Some(parse_number(arg, &value()?)? as usize),
+            "--delay" => {
+                frame_delay_ms = parse_number(arg, &value()?)?;
+                if frame_delay_ms > MAX_FRAME_DELAY_MS {
+                    return Err(format!(
+                        "Each move can be shown at most {} ms",
+                        MAX_FRAME_DELAY_MS
+                    ));
+                }
+            }
+            "--output" => output = Some(value()?),
+            "--resource-dir" => resource_dir = value()?,
+            "--flip" => options.flipped = true,
+            "--no-coordinates" => options.coordinates = false,
+            "--size" => {
+                let size = parse_number(arg, &value()?)?;
+                if size < MIN_BOARD_SIZE {
+                    return Err(format!(
+                        "The board must be at least {} pixels wide",
+                        MIN_BOARD_SIZE
+                    ));
+                }
+                options.board_size = size;
+            }
+            "--square" => {
+                let text = value()?;
+                let marker = parse_square_marker(&text)
+                    .ok_or_else(|| format!("Invalid square marker '{}'", text))?;
+                options.squares.push(marker);
+            }
+            "--arrow" => {
+                let text = value()?;
+                let arrow =
+                    parse_arrow(&text).ok_or_else(|| format!("Invalid arrow '{}'", text))?;
+                options.arrows.push(arrow);
+            }
+            _ => return Err(format!("Unexpected argument '{}'\n\n{}", arg, USAGE)),
+        }
+    }
+    let output = output.ok_or_else(|| format!("Missing option '--output'\n\n{}", USAGE))?;
+    let mut game = game.unwrap_or_default();
+    if let Some(ply) = ply {
+        if ply > game.executed_moves().len() {
+            return Err(format!(
+                "The game has only {} plies",
+                game.executed_moves().len()
+            ));
+        }
+        game = game.position_after(ply);
+    }
+    options.last_move = DiagramOptions::for_game(&game, PieceColor::White).last_move;
+    let extension = Path::new(&output)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    let content = match extension.as_deref() {
+        Some("svg") => export_svg(&game, &options).into_bytes(),
+        Some("png") => {
+            let sprites = PieceSprites::load_default(&resource_dir)?;
+            export_png(&game, &options, &sprites)?
+        }
+        Some("gif") => {
+            let sprites = PieceSprites::load_default(&resource_dir)?;
+            export_gif(&game, &options, &sprites, frame_delay_ms)?
+        }
+        _ => {
+            return Err(format!(
+                "Unknown diagram format of '{}', use .svg, .png or .gif",
+                output
+            ))
+        }
+    };
+    fs::write(&output, content).map_err(|error| format!("{}: {}", output, error))
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value of option '{}': {}", option, value))
+}
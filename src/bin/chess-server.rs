@@ -0,0 +1,62 @@
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::{env, process};
+
+use chess::server::{GameServer, DEFAULT_PORT};
+
+const USAGE: &str = "Usage: chess-server [OPTIONS]
+
+Serve chess games over HTTP and WebSocket, so other tools can use the rules of this crate:
+
+  POST   /games               Create a game, optionally from a position: {\"fen\": \"<FEN>\"}
+  GET    /games               List the states of all games
+  GET    /games/<ID>          Get the state of a game: FEN, moves, legal moves and status
+  POST   /games/<ID>/moves    Execute a move in SAN or coordinate notation: {\"move\": \"Nf3\"}
+  DELETE /games/<ID>          Delete a game
+  GET    /games/<ID>/updates  Receive the state after each move over a WebSocket
+
+  --port <PORT>          TCP port to listen on [default: 8080]
+  --bind <ADDRESS>       Address to listen on, e.g. 0.0.0.0 for all interfaces [default: 127.0.0.1]
+  -h, --help             Print this help";
+
+/** Main entry point of the game server. */
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err(error) = run(&args) {
+        eprintln!("{}", error);
+        process::exit(2);
+    }
+}
+
+/** Serve games as configured by given command line arguments until the server fails. */
+fn run(args: &[String]) -> Result<(), String> {
+    let mut port = DEFAULT_PORT;
+    let mut address = "127.0.0.1".to_string();
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        let mut value = || {
+            remaining
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value of option '{}'", arg))
+        };
+        match arg.as_str() {
+            "--port" => {
+                let text = value()?;
+                port = text
+                    .parse()
+                    .map_err(|_| format!("Invalid port '{}'", text))?;
+            }
+            "--bind" => address = value()?,
+            _ => return Err(format!("Unexpected argument '{}'\n\n{}", arg, USAGE)),
+        }
+    }
+    let listener = TcpListener::bind((address.as_str(), port))
+        .map_err(|error| format!("Cannot listen on {}:{}: {}", address, port, error))?;
+    println!("Serving games on http://{}:{}/games", address, port);
+    Arc::new(GameServer::new()).serve(listener)
+}
@@ -0,0 +1,128 @@
+use std::{env, fs, process};
+
+use chess::tournament::engine::Engine;
+use chess::tournament::{parse_openings, Adjudication, Score, Tournament};
+
+const USAGE: &str = "Usage: chess-tournament [OPTIONS]
+
+Play a match of two engines, which alternate colors and play each opening with both colors, and
+report the score and the Elo difference of the first engine.
+
+  --first <ENGINE>       First engine: builtin:<DEPTH> or uci:<PATH> [default: builtin:2]
+  --second <ENGINE>      Second engine [default: builtin:1]
+  --games <N>            Number of games [default: 10]
+  --openings <FILE>      Start positions in FEN or EPD, one per line [default: start position]
+  --movetime <MS>        Time per move of an external engine [default: 100]
+  --max-moves <N>        Adjudicate a draw after N full moves, 0 for no limit [default: 200]
+  --material <PAWNS>     Adjudicate a win for a side ahead in material by PAWNS for 8 plies
+  --pgn <FILE>           Write all games in Portable Game Notation
+  -h, --help             Print this help";
+
+/** Main entry point of the tournament runner. */
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err(error) = run(&args) {
+        eprintln!("{}", error);
+        process::exit(2);
+    }
+}
+
+/** Play the tournament described by given command line arguments and print its report. */
+fn run(args: &[String]) -> Result<(), String> {
+    let mut first = "builtin:2".to_string();
+    let mut second = "builtin:1".to_string();
+    let mut movetime_ms = 100;
+    let mut pgn_file: Option<String> = None;
+    let mut tournament = Tournament {
+        games: 10,
+        openings: Vec::new(),
+        adjudication: Adjudication::default(),
+    };
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        let mut value = || {
+            remaining
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value of option '{}'", arg))
+        };
+        match arg.as_str() {
+            "--first" => first = value()?,
+            "--second" => second = value()?,
+            "--games" => tournament.games = parse_number(arg, &value()?)? as usize,
+            "--openings" => {
+                let path = value()?;
+                let text =
+                    fs::read_to_string(&path).map_err(|error| format!("{}: {}", path, error))?;
+                tournament.openings =
+                    parse_openings(&text).map_err(|error| format!("{}: {}", path, error))?;
+            }
+            "--movetime" => movetime_ms = parse_number(arg, &value()?)?,
+            "--max-moves" => {
+                tournament.adjudication.max_moves = match parse_number(arg, &value()?)? {
+                    0 => None,
+                    moves => Some(moves as usize),
+                }
+            }
+            "--material" => {
+                let pawns = parse_number(arg, &value()?)?.max(1);
+                tournament.adjudication.material_threshold = Some(pawns as i32);
+            }
+            "--pgn" => pgn_file = Some(value()?),
+            _ => return Err(format!("Unexpected argument '{}'\n\n{}", arg, USAGE)),
+        }
+    }
+    let mut first = Engine::parse(&first, movetime_ms)?;
+    let mut second = Engine::parse(&second, movetime_ms)?;
+    let (first_name, second_name) = (first.name(), second.name());
+    let records = tournament.play(&mut first, &mut second, |number, record| {
+        println!(
+            "Game {}: {} - {} {} ({})",
+            number,
+            record.white,
+            record.black,
+            record.result.pgn(),
+            record.reason
+        );
+    });
+    if let Some(path) = pgn_file {
+        let pgn: Vec<String> = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| record.pgn(index + 1))
+            .collect();
+        fs::write(&path, pgn.join("\n")).map_err(|error| format!("{}: {}", path, error))?;
+    }
+    let score = Score::of(&records);
+    println!(
+        "Score of {} vs {}: {} - {} - {} [{:.3}] {}",
+        first_name,
+        second_name,
+        score.wins,
+        score.losses,
+        score.draws,
+        score.ratio(),
+        score.games()
+    );
+    let difference = match score.elo_difference() {
+        Some(elo) => format!("{:.1}", elo),
+        None if score.ratio() < 0.5 => "-inf".to_string(),
+        None => "inf".to_string(),
+    };
+    let margin = match score.elo_margin() {
+        Some(elo) => format!("{:.1}", elo),
+        None => "inf".to_string(),
+    };
+    println!("Elo difference: {} +/- {}", difference, margin);
+    Ok(())
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value of option '{}': {}", option, value))
+}
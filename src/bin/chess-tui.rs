@@ -0,0 +1,502 @@
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use std::{env, process, thread};
+
+use chess::config::{PlayerType, Settings, USAGE};
+use chess::domain::book::OpeningBook;
+use chess::domain::chessboard::BoardSquare;
+use chess::domain::evaluation::Evaluation;
+use chess::domain::game::diagram::DiagramStyle;
+use chess::domain::game::{ChessGame, Move};
+use chess::domain::pgn::export_pgn;
+use chess::domain::pieces::{PieceColor, PieceType};
+use chess::domain::search::best_move;
+use chess::network::protocol::Message;
+use chess::network::session::{NetworkEvent, NetworkSession};
+use chess::network::NetworkRole;
+
+const HELP: &str =
+    "Terminal frontend of RustyChess. Enter moves in Standard Algebraic Notation, e.g.
+Nf3, or in coordinate notation, e.g. g1f3. Further commands:
+
+  moves   List all possible moves
+  undo    Take back the last move of the user
+  flip    Turn the board around
+  fen     Print the position in Forsyth-Edwards Notation
+  eval    Print the static evaluation of the position term by term, in pawns for White
+  book    List the moves of the opening book given with --book in the position with their weights
+  pgn     Print the game in Portable Game Notation
+  resign  Give up the game
+  help    Print this help
+  quit    End the game
+
+Commands of a network game, which is started with --host or --connect. A game watched with
+--watch only shows the moves:
+
+  draw         Offer a draw to the opponent, or accept the draw offered by the opponent
+  decline      Decline the draw offered by the opponent
+  chat <TEXT>  Send a message to the opponent
+
+Colors are disabled if the environment variable NO_COLOR is set. The options below are shared
+with the graphical frontend; time control, theme and resource directory are ignored here.";
+
+/** ANSI 256 color codes of the board. */
+const LIGHT_SQUARE: u8 = 180;
+const DARK_SQUARE: u8 = 137;
+const LAST_MOVE_SQUARE: u8 = 143;
+const CHECK_SQUARE: u8 = 167;
+const WHITE_PIECE: u8 = 231;
+const BLACK_PIECE: u8 = 16;
+
+/** How often the moves of a remote opponent are looked for while waiting for input. */
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/** Main entry point of the terminal frontend: Moves are read from the standard input, the board is
+printed to the standard output after each move. */
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}\n\n{}", HELP, USAGE);
+        return;
+    }
+    match Settings::load(&args).and_then(TerminalGame::new) {
+        Ok(mut terminal_game) => terminal_game.run(),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    }
+}
+
+/** A game played in a plain terminal without any graphics. */
+struct TerminalGame {
+    game: ChessGame,
+    settings: Settings,
+    bottom_color: PieceColor,
+    colored: bool,
+    network: Option<NetworkSession>,
+    /** The end of the game by resignation or agreement. */
+    game_end: Option<String>,
+    /** Whether the opponent of a network game has offered a draw which is not answered yet. */
+    draw_offered: bool,
+    /** The opening book whose moves the computer plays, if any. */
+    book: Option<OpeningBook>,
+}
+
+impl TerminalGame {
+    fn new(mut settings: Settings) -> Result<Self, String> {
+        let mut game = match &settings.fen {
+            Some(fen) => ChessGame::from_fen(fen)?,
+            None => ChessGame::new(),
+        };
+        let network = match settings.network.clone() {
+            Some(NetworkRole::Host(port)) => {
+                let local_color = settings
+                    .remote_color()
+                    .unwrap_or(PieceColor::Black)
+                    .opponent();
+                println!("Waiting for the opponent on port {}...", port);
+                Some(NetworkSession::host(port, local_color)?)
+            }
+            Some(NetworkRole::Guest(address)) => {
+                let (session, host_game) = NetworkSession::connect(&address)?;
+                if let Some(local_color) = session.local_color() {
+                    settings.set_remote_color(local_color.opponent());
+                }
+                game = host_game;
+                Some(session)
+            }
+            Some(NetworkRole::Spectator(address)) => {
+                let (session, host_game) = NetworkSession::watch(&address)?;
+                println!("Watching the game of {}", address);
+                game = host_game;
+                Some(session)
+            }
+            None => None,
+        };
+        // The side of the only human player or of the local player is shown at the bottom.
+        let local_color = network.as_ref().and_then(|network| network.local_color());
+        let bottom_color = match (settings.white, settings.black, local_color) {
+            (_, _, Some(local_color)) => local_color,
+            (PlayerType::Computer, PlayerType::Human, _) => PieceColor::Black,
+            _ => PieceColor::White,
+        };
+        Ok(Self {
+            game,
+            bottom_color,
+            colored: env::var_os("NO_COLOR").is_none(),
+            network,
+            game_end: None,
+            draw_offered: false,
+            book: settings
+                .book
+                .as_deref()
+                .map(OpeningBook::load)
+                .transpose()?,
+            settings,
+        })
+    }
+
+    /** Play the game until it is over, the user quits or the standard input ends. */
+    fn run(&mut self) {
+        let lines = read_lines();
+        self.print_board();
+        self.prompt();
+        while !self.is_game_over() {
+            if self.settings.player(self.game.next_move_color()) == PlayerType::Computer {
+                self.play_computer_move();
+                self.prompt();
+                continue;
+            }
+            self.poll_network();
+            match lines.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => {
+                    if !self.evaluate_line(line.trim()) {
+                        return;
+                    }
+                    self.prompt();
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    println!();
+                    return;
+                }
+            }
+        }
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.game_end.is_some() || self.game.is_check_mate() || self.game.is_stalemate()
+    }
+
+    /** Ask the user for the next move, if it is the user's turn. */
+    fn prompt(&self) {
+        let color = self.game.next_move_color();
+        if self.settings.player(color) == PlayerType::Human && !self.is_game_over() {
+            print!("{:?}> ", color);
+            io::stdout().flush().ok();
+        }
+    }
+
+    /** Execute a given move or command entered by the user. Returns whether the game goes on. */
+    fn evaluate_line(&mut self, line: &str) -> bool {
+        match line.to_lowercase().as_str() {
+            "" => {}
+            "quit" | "exit" => return false,
+            "help" => println!("{}", HELP),
+            "moves" => {
+                let moves: Vec<String> = self
+                    .game
+                    .possible_move_choices()
+                    .iter()
+                    .map(|(chess_move, promotion)| self.game.san(chess_move, *promotion))
+                    .collect();
+                println!("{}", moves.join(" "));
+            }
+            "undo" if self.network.is_some() => {
+                println!("Moves cannot be taken back in a network game")
+            }
+            "undo" => self.undo(),
+            "resign" | "draw" | "decline" | "chat" if self.is_spectator() => {
+                println!("Spectators cannot play")
+            }
+            _ if line.starts_with("chat ") && self.is_spectator() => {
+                println!("Spectators cannot chat")
+            }
+            "resign" => {
+                let color = self.local_color();
+                self.send(Message::Resign);
+                self.end_game(format!("{:?} resigns", color));
+            }
+            "draw" | "decline" | "chat" if self.network.is_none() => {
+                println!("{} is only possible in a network game", line)
+            }
+            "draw" if self.draw_offered => {
+                self.send(Message::AcceptDraw);
+                self.end_game("Draw agreed".to_string());
+            }
+            "draw" => {
+                self.send(Message::OfferDraw);
+                println!("Draw offered to the opponent");
+            }
+            "decline" if self.draw_offered => {
+                self.draw_offered = false;
+                self.send(Message::DeclineDraw);
+            }
+            "decline" => println!("There is no draw offer to decline"),
+            "flip" => {
+                self.bottom_color = self.bottom_color.opponent();
+                self.print_board();
+            }
+            "fen" => println!("{}", self.game.fen()),
+            "eval" => println!("{}", Evaluation::of(&self.game)),
+            "book" => self.print_book_moves(),
+            "pgn" => println!("{}", export_pgn(&self.game)),
+            _ if line.starts_with("chat ") => match self.network.is_some() {
+                true => self.send(Message::Chat(line["chat ".len()..].to_string())),
+                false => println!("chat is only possible in a network game"),
+            },
+            _ => match self.game.parse_move(line) {
+                _ if self.settings.player(self.game.next_move_color()) != PlayerType::Human => {
+                    println!("It is not your turn")
+                }
+                Some((chess_move, promotion)) => {
+                    self.execute_move(&chess_move, promotion);
+                    self.print_board();
+                }
+                None => println!("Unknown or impossible move: {} (type help for help)", line),
+            },
+        }
+        true
+    }
+
+    /** Take back the last move of the user together with the computer's reply, if any. */
+    fn undo(&mut self) {
+        let move_count = self.game.executed_moves().len();
+        let last_mover = self.game.next_move_color().opponent();
+        let undone = match self.settings.player(last_mover) {
+            PlayerType::Computer | PlayerType::Remote => 2,
+            PlayerType::Human => 1,
+        };
+        if move_count < undone {
+            println!("There is no move to take back");
+            return;
+        }
+        self.game.go_to_ply(move_count - undone);
+        self.print_board();
+    }
+
+    fn play_computer_move(&mut self) {
+        let color = self.game.next_move_color();
+        let book_move = self
+            .book
+            .as_ref()
+            .and_then(|book| book.choose_move(&self.game));
+        let from_book = book_move.is_some();
+        if !from_book {
+            println!("{:?} is thinking...", color);
+        }
+        let Some((chess_move, promotion)) =
+            book_move.or_else(|| best_move(&self.game, self.settings.search_depth))
+        else {
+            return;
+        };
+        let san = self.game.san(&chess_move, promotion);
+        self.execute_move(&chess_move, promotion);
+        match from_book {
+            true => println!("{:?} plays {} (book move)", color, san),
+            false => println!("{:?} plays {}", color, san),
+        }
+        self.print_board();
+    }
+
+    /** Print the moves of the opening book in the current position with their weights. */
+    fn print_book_moves(&self) {
+        let Some(book) = &self.book else {
+            println!("There is no opening book, it is given with --book");
+            return;
+        };
+        let moves = book.moves(&self.game);
+        if moves.is_empty() {
+            println!("The position is not in the opening book");
+        }
+        let total: u32 = moves.iter().map(|book_move| book_move.weight as u32).sum();
+        for book_move in moves {
+            let san = self.game.san(&book_move.chess_move, book_move.promotion);
+            let percent = match total {
+                0 => 0.0,
+                total => book_move.weight as f64 * 100.0 / total as f64,
+            };
+            println!("{:<8}{:>6}  {:5.1}%", san, book_move.weight, percent);
+        }
+    }
+
+    /** Execute a given move of a local player and send it to the opponent of a network game. */
+    fn execute_move(&mut self, chess_move: &Move, promotion: Option<PieceType>) {
+        self.game.execute_move(chess_move);
+        if let Some(new_type) = promotion {
+            self.game.exchange_promotion_pawn(new_type);
+        }
+        if let Some(network) = &mut self.network {
+            network.send_moves(&self.game);
+        }
+        self.draw_offered = false;
+    }
+
+    /** The color of the local player of a network game, otherwise the color to move next. */
+    fn local_color(&self) -> PieceColor {
+        self.network
+            .as_ref()
+            .and_then(|network| network.local_color())
+            .unwrap_or_else(|| self.game.next_move_color())
+    }
+
+    fn is_spectator(&self) -> bool {
+        self.network
+            .as_ref()
+            .is_some_and(|network| network.is_spectator())
+    }
+
+    /** Send a given message to the opponent, if this is a network game. */
+    fn send(&mut self, message: Message) {
+        if let Some(network) = &mut self.network {
+            network.send(message);
+        }
+    }
+
+    /** End the game with a given result apart from the rules of chess and show it. */
+    fn end_game(&mut self, result: String) {
+        self.game_end = Some(result);
+        println!("{}", self.status());
+    }
+
+    /** Handle everything the opponent of a network game has done since the last call. */
+    fn poll_network(&mut self) {
+        let Some(network) = &mut self.network else {
+            return;
+        };
+        let is_spectator = network.is_spectator();
+        for event in network.poll(&self.game) {
+            println!();
+            match event {
+                NetworkEvent::Connected if is_spectator => {
+                    println!("The host is connected")
+                }
+                NetworkEvent::Connected => {
+                    println!("The opponent is connected");
+                    self.print_board();
+                }
+                NetworkEvent::Disconnected => {
+                    println!("The connection is lost, waiting for reconnection...")
+                }
+                NetworkEvent::Move(chess_move, promotion) => {
+                    let color = self.game.next_move_color();
+                    let san = self.game.san(&chess_move, promotion);
+                    self.game.execute_move(&chess_move);
+                    if let Some(new_type) = promotion {
+                        self.game.exchange_promotion_pawn(new_type);
+                    }
+                    println!("{:?} plays {}", color, san);
+                    self.print_board();
+                }
+                NetworkEvent::DrawOffered => {
+                    self.draw_offered = true;
+                    println!("The opponent offers a draw: Type draw to accept or decline");
+                }
+                NetworkEvent::DrawAccepted => self.end_game("Draw agreed".to_string()),
+                NetworkEvent::DrawDeclined => println!("The opponent declines the draw"),
+                NetworkEvent::Resigned(color) => self.end_game(format!("{:?} resigns", color)),
+                NetworkEvent::Chat(text) => println!("Opponent: {}", text),
+                NetworkEvent::Synced(game) => {
+                    self.game = *game;
+                    println!("The position is synchronized with the host");
+                    self.print_board();
+                }
+                NetworkEvent::SpectatorJoined => println!("A spectator joined the game"),
+                NetworkEvent::SpectatorLeft => println!("A spectator left the game"),
+                NetworkEvent::Error(error) => println!("Network error: {}", error),
+            }
+            self.prompt();
+        }
+    }
+
+    /** Print the board seen from the bottom color together with the state of the game. */
+    fn print_board(&self) {
+        println!();
+        if !self.colored {
+            let style = DiagramStyle {
+                unicode: true,
+                footer: false,
+                flipped: self.bottom_color == PieceColor::Black,
+                ..DiagramStyle::default()
+            };
+            println!("{}\n{}", self.game.diagram(style), self.status());
+            return;
+        }
+        let last_move = self.game.executed_moves().last().cloned();
+        let is_last_move_square = |square: BoardSquare| {
+            last_move.as_ref().is_some_and(|executed_move| {
+                executed_move.start_square == square || executed_move.target_square == square
+            })
+        };
+        let checked_king = self.game.pieces.iter().find(|piece| {
+            piece.piece_type == PieceType::King
+                && piece.color == self.game.next_move_color()
+                && self.game.is_check()
+        });
+        let positions: Vec<i8> = match self.bottom_color {
+            PieceColor::White => (0..8).collect(),
+            PieceColor::Black => (0..8).rev().collect(),
+        };
+        for y in &positions {
+            let mut line = format!("{} ", 8 - y);
+            for x in positions.iter().rev().map(|x| 7 - x) {
+                let piece = self.game.piece_at((x, *y));
+                let square = *self.game.board.square_at((x, *y)).unwrap();
+                let background = if checked_king.is_some_and(|king| king.square == square) {
+                    CHECK_SQUARE
+                } else if is_last_move_square(square) {
+                    LAST_MOVE_SQUARE
+                } else if (x + y) % 2 == 0 {
+                    LIGHT_SQUARE
+                } else {
+                    DARK_SQUARE
+                };
+                // The solid glyphs are colored, so both colors look alike apart from their color.
+                let (foreground, glyph) = match piece {
+                    Some(piece) if piece.color == PieceColor::White => {
+                        (WHITE_PIECE, piece.piece_type.glyph(PieceColor::Black))
+                    }
+                    Some(piece) => (BLACK_PIECE, piece.piece_type.glyph(PieceColor::Black)),
+                    None => (BLACK_PIECE, ' '),
+                };
+                line.push_str(&format!(
+                    "\x1b[48;5;{}m\x1b[38;5;{}m {} \x1b[0m",
+                    background, foreground, glyph
+                ));
+            }
+            println!("{}", line);
+        }
+        let files: String = positions
+            .iter()
+            .rev()
+            .map(|x| format!(" {} ", (b'a' + (7 - x) as u8) as char))
+            .collect();
+        println!("  {}", files);
+        println!("{}", self.status());
+    }
+
+    /** The state of the game: The color to move next and whether it is in check, or the result. */
+    fn status(&self) -> String {
+        let color = self.game.next_move_color();
+        if let Some(game_end) = &self.game_end {
+            game_end.clone()
+        } else if self.game.is_check_mate() {
+            format!("Checkmate - {:?} wins", color.opponent())
+        } else if self.game.is_stalemate() {
+            "Stalemate - Draw".to_string()
+        } else if self.game.is_check() {
+            format!("{:?} to move - Check", color)
+        } else {
+            format!("{:?} to move", color)
+        }
+    }
+}
+
+/** The lines of the standard input, read by a thread of their own so that waiting for them can be
+interrupted, e.g. by a move of a network opponent. */
+fn read_lines() -> Receiver<String> {
+    let (sender, receiver) = channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
@@ -0,0 +1,256 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use toml::{Table, Value};
+
+use crate::domain::clock::TimeControl;
+use crate::domain::pieces::PieceColor;
+use crate::network::NetworkRole;
+
+const CONFIG_DIR: &str = "rusty_chess";
+const CONFIG_FILE: &str = "config.toml";
+const DEFAULT_RESOURCE_DIR: &str = "resources";
+const DEFAULT_SEARCH_DEPTH: u32 = 2;
+const DEFAULT_PGN_FILE: &str = "game.pgn";
+
+/** The command line usage of the application. */
+pub const USAGE: &str = "Usage: chess [OPTIONS]
+
+Options override the values of the configuration file, which uses the same names with
+underscores, e.g. time_control = \"5+3\".
+
+  --config <FILE>        Configuration file [default: <user config dir>/rusty_chess/config.toml]
+  --fen <FEN>            Start position in Forsyth-Edwards Notation
+  --white <PLAYER>       Player of White: human, computer or remote [default: human]
+  --black <PLAYER>       Player of Black: human, computer or remote [default: human]
+  --time-control <TC>    Minutes per player plus increment in seconds, e.g. 5+3
+  --theme <NAME>         Theme of the board, e.g. classic, wood, ocean or contrast
+  --resource-dir <DIR>   Directory of the sprite sheets and themes [default: resources]
+  --search-depth <N>     Number of moves (plies) the computer looks ahead [default: 2]
+  --pgn-file <FILE>      File the game is saved to and loaded from [default: game.pgn]
+  --book <FILE>          Opening book in the Polyglot format (.bin), whose moves the computer
+                         plays as long as the position is in the book
+  --host <PORT>          Wait for the remote opponent of a network game, who plays Black unless
+                         White is remote
+  --connect <ADDRESS>    Join the network game of a host, e.g. localhost:7878; the host decides
+                         on the colors and the position
+  --watch <ADDRESS>      Watch the network game of a host without playing, e.g. localhost:7878
+  -h, --help             Print this help";
+
+/** Who decides on the moves of one side of the game. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayerType {
+    Human,
+    Computer,
+    /** The opponent of a network game, playing on another computer. */
+    Remote,
+}
+
+/** The settings of the application, read from the configuration file and the command line. */
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub fen: Option<String>,
+    pub white: PlayerType,
+    pub black: PlayerType,
+    pub time_control: Option<TimeControl>,
+    pub theme: Option<String>,
+    pub resource_dir: String,
+    pub search_depth: u32,
+    /** The path of the PGN file the game is saved to and loaded from. */
+    pub pgn_file: String,
+    /** The path of the opening book of the computer player, if any. */
+    pub book: Option<String>,
+    pub network: Option<NetworkRole>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fen: None,
+            white: PlayerType::Human,
+            black: PlayerType::Human,
+            time_control: None,
+            theme: None,
+            resource_dir: DEFAULT_RESOURCE_DIR.to_string(),
+            search_depth: DEFAULT_SEARCH_DEPTH,
+            pgn_file: DEFAULT_PGN_FILE.to_string(),
+            book: None,
+            network: None,
+        }
+    }
+}
+
+impl Settings {
+    /** The settings of the configuration file, overridden by given command line arguments. The
+    configuration file is optional unless it is given on the command line. */
+    pub fn load(args: &[String]) -> Result<Settings, String> {
+        let options = parse_options(args)?;
+        let mut settings = Settings::default();
+        let config_file = options
+            .iter()
+            .find(|(name, _)| name == "config")
+            .map(|(_, value)| PathBuf::from(value));
+        let path = config_file.clone().or_else(default_config_file);
+        if let Some(path) = path.filter(|path| config_file.is_some() || path.exists()) {
+            let text = fs::read_to_string(&path)
+                .map_err(|error| format!("{}: {}", path.display(), error))?;
+            let document: Table = text
+                .parse()
+                .map_err(|error| format!("{}: {}", path.display(), error))?;
+            for (name, value) in &document {
+                let text = match value {
+                    Value::String(text) => text.clone(),
+                    Value::Integer(number) => number.to_string(),
+                    Value::Float(number) => number.to_string(),
+                    Value::Boolean(value) => value.to_string(),
+                    _ => {
+                        let error = format!("Invalid value of setting '{}'", name);
+                        return Err(format!("{}: {}", path.display(), error));
+                    }
+                };
+                settings
+                    .apply(name, &text)
+                    .map_err(|error| format!("{}: {}", path.display(), error))?;
+            }
+        }
+        for (name, value) in options.iter().filter(|(name, _)| name != "config") {
+            settings.apply(name, value)?;
+        }
+        settings.check_remote_players()?;
+        Ok(settings)
+    }
+
+    /** The player of a given color. */
+    pub fn player(&self, color: PieceColor) -> PlayerType {
+        match color {
+            PieceColor::White => self.white,
+            PieceColor::Black => self.black,
+        }
+    }
+
+    /** The color of the remote player, if any. */
+    pub fn remote_color(&self) -> Option<PieceColor> {
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find(|color| self.player(*color) == PlayerType::Remote)
+    }
+    /** Let the remote player play a given color, e.g. the one assigned by the host of a network
+    game. The local player keeps its type unless it was remote before, then it becomes human. */
+    pub fn set_remote_color(&mut self, color: PieceColor) {
+        let (remote, local) = match color {
+            PieceColor::White => (&mut self.white, &mut self.black),
+            PieceColor::Black => (&mut self.black, &mut self.white),
+        };
+        *remote = PlayerType::Remote;
+        if *local == PlayerType::Remote {
+            *local = PlayerType::Human;
+        }
+    }
+
+    /** Check that there is a remote player only in a network game. The remote player of a host
+    defaults to Black, the one of a guest is assigned when connecting. Both players of a spectator
+    are remote. */
+    fn check_remote_players(&mut self) -> Result<(), String> {
+        let remote_count = [self.white, self.black]
+            .iter()
+            .filter(|player| **player == PlayerType::Remote)
+            .count();
+        match (&self.network, remote_count) {
+            (None, 0) | (Some(NetworkRole::Host(_)), 1) | (Some(NetworkRole::Guest(_)), _) => {
+                Ok(())
+            }
+            (None, _) => {
+                Err("A remote player requires the option --host, --connect or --watch".to_string())
+            }
+            (Some(NetworkRole::Host(_)), 0) => {
+                self.black = PlayerType::Remote;
+                Ok(())
+            }
+            (Some(NetworkRole::Host(_)), _) => Err("Only one player can be remote".to_string()),
+            (Some(NetworkRole::Spectator(_)), _) => {
+                self.white = PlayerType::Remote;
+                self.black = PlayerType::Remote;
+                Ok(())
+            }
+        }
+    }
+
+    /** Set the setting with a given name to a given value. */
+    fn apply(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "fen" => self.fen = Some(value.to_string()),
+            "white" => self.white = parse_player(value)?,
+            "black" => self.black = parse_player(value)?,
+            "time_control" => self.time_control = Some(TimeControl::parse(value)?),
+            "theme" => self.theme = Some(value.to_string()),
+            "resource_dir" => self.resource_dir = value.to_string(),
+            "host" => {
+                let port = value
+                    .parse()
+                    .map_err(|_| format!("Invalid port '{}'", value))?;
+                self.network = Some(NetworkRole::Host(port))
+            }
+            "connect" => self.network = Some(NetworkRole::Guest(value.to_string())),
+            "watch" => self.network = Some(NetworkRole::Spectator(value.to_string())),
+            "search_depth" => {
+                self.search_depth = value
+                    .parse()
+                    .ok()
+                    .filter(|depth| *depth > 0)
+                    .ok_or_else(|| format!("Invalid search depth '{}'", value))?
+            }
+            "pgn_file" => self.pgn_file = value.to_string(),
+            "book" => self.book = Some(value.to_string()),
+            _ => return Err(format!("Unknown setting '{}'", name)),
+        }
+        Ok(())
+    }
+}
+
+/** The name and value of all options of given command line arguments, e.g. ("time_control", "5+3")
+for "--time-control 5+3" or "--time-control=5+3". */
+fn parse_options(args: &[String]) -> Result<Vec<(String, String)>, String> {
+    let mut result = Vec::new();
+    let mut remaining = args.iter();
+    while let Some(arg) = remaining.next() {
+        let option = arg
+            .strip_prefix("--")
+            .ok_or_else(|| format!("Unexpected argument '{}'\n\n{}", arg, USAGE))?;
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None => {
+                let value = remaining
+                    .next()
+                    .ok_or_else(|| format!("Missing value of option '{}'", arg))?;
+                (option, value.clone())
+            }
+        };
+        result.push((name.replace('-', "_"), value));
+    }
+    Ok(result)
+}
+
+fn parse_player(text: &str) -> Result<PlayerType, String> {
+    match text.to_lowercase().as_str() {
+        "human" => Ok(PlayerType::Human),
+        "computer" => Ok(PlayerType::Computer),
+        "remote" => Ok(PlayerType::Remote),
+        _ => Err(format!(
+            "Invalid player '{}', expected human, computer or remote",
+            text
+        )),
+    }
+}
+
+/** The configuration file within the configuration directory of the user, if there is one. */
+fn default_config_file() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => match env::var_os("APPDATA") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        },
+    };
+    Some(config_dir.join(CONFIG_DIR).join(CONFIG_FILE))
+}
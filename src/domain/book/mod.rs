@@ -0,0 +1,257 @@
+mod keys;
+
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::domain::book::keys::RANDOM64;
+use crate::domain::chessboard::BoardSquare;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::{PieceColor, PieceType};
+
+/** The size in bytes of an entry of a Polyglot book. */
+const ENTRY_SIZE: usize = 16;
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/** An entry of a Polyglot book: A move in the position of a key with a weight. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BookEntry {
+    key: u64,
+    raw_move: u16,
+    weight: u16,
+}
+
+/** A move of an opening book in a position together with its weight. The higher the weight, the
+more often the move is played. */
+#[derive(Debug, Clone)]
+pub struct BookMove {
+    pub chess_move: Move,
+    pub promotion: Option<PieceType>,
+    pub weight: u16,
+}
+
+/** An opening book in the Polyglot format (.bin) as used by many chess programs: Entries of a key of
+a position, a move and its weight, sorted by key. */
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    entries: Vec<BookEntry>,
+}
+
+impl OpeningBook {
+    /** The opening book of the Polyglot file at a given path. */
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|error| format!("{}: {}", path, error))?;
+        Self::from_bytes(&bytes).map_err(|error| format!("{}: {}", path, error))
+    }
+
+    /** The opening book of given bytes in the Polyglot format. */
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err("Not an opening book in the Polyglot format".to_string());
+        }
+        let mut entries: Vec<BookEntry> = bytes
+            .chunks(ENTRY_SIZE)
+            .map(|entry| BookEntry {
+                key: u64::from_be_bytes(entry[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes([entry[8], entry[9]]),
+                weight: u16::from_be_bytes([entry[10], entry[11]]),
+            })
+            .collect();
+        // The entries of a valid book are sorted already, sorting keeps a careless one usable.
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Self { entries })
+    }
+
+    /** All moves of this book which are possible in the current position of a given game, the
+    move with the highest weight first. */
+    pub fn moves(&self, game: &ChessGame) -> Vec<BookMove> {
+        let key = polyglot_key(game);
+        let first = self.entries.partition_point(|entry| entry.key < key);
+        let mut result: Vec<BookMove> = self.entries[first..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| {
+                let (chess_move, promotion) = decode_move(game, entry.raw_move)?;
+                Some(BookMove {
+                    chess_move,
+                    promotion,
+                    weight: entry.weight,
+                })
+            })
+            .collect();
+        result.sort_by_key(|book_move| Reverse(book_move.weight));
+        result
+    }
+
+    /** A move of this book for the current position of a given game, chosen at random according to
+    the weights of the moves. None if the position is not in the book. */
+    pub fn choose_move(&self, game: &ChessGame) -> Option<(Move, Option<PieceType>)> {
+        let moves: Vec<BookMove> = self
+            .moves(game)
+            .into_iter()
+            .filter(|book_move| book_move.weight > 0)
+            .collect();
+        let total: u64 = moves.iter().map(|book_move| book_move.weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        // Each hasher of a new random state is seeded differently, which is random enough here.
+        let mut chosen = RandomState::new().build_hasher().finish() % total;
+        for book_move in moves {
+            if chosen < book_move.weight as u64 {
+                return Some((book_move.chess_move, book_move.promotion));
+            }
+            chosen -= book_move.weight as u64;
+        }
+        None
+    }
+
+    /** Whether the last move of a given game is a move of this book. */
+    pub fn is_book_move(&self, game: &ChessGame) -> bool {
+        let executed_moves = game.executed_moves();
+        let Some(last_move) = executed_moves.last() else {
+            return false;
+        };
+        let previous = game.position_after(executed_moves.len() - 1);
+        self.moves(&previous).iter().any(|book_move| {
+            book_move.chess_move.piece.square == last_move.start_square
+                && book_move.chess_move.target == last_move.target_square
+                && book_move.promotion == last_move.promotion
+        })
+    }
+}
+
+/** The key of the current position of a given game in a Polyglot book. An en passant capture only
+counts if a pawn of the color to move can make it. */
+pub fn polyglot_key(game: &ChessGame) -> u64 {
+    let mut result = 0;
+    for piece in &game.pieces {
+        let type_index = match piece.piece_type {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        };
+        let kind = 2 * type_index + (piece.color == PieceColor::White) as usize;
+        let (x, y) = piece.square.position();
+        result ^= RANDOM64[64 * kind + 8 * (7 - y) as usize + x as usize];
+    }
+    let fen = game.fen();
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    for (index, right) in ['K', 'Q', 'k', 'q'].iter().enumerate() {
+        if fields[2].contains(*right) {
+            result ^= RANDOM64[CASTLING_OFFSET + index];
+        }
+    }
+    let color = game.next_move_color();
+    if let Some(target) = BoardSquare::from_notation(fields[3]) {
+        let (x, y) = target.position();
+        let pawn_y = match color {
+            PieceColor::White => y + 1,
+            PieceColor::Black => y - 1,
+        };
+        let can_capture = [x - 1, x + 1].iter().any(|pawn_x| {
+            game.piece_at((*pawn_x, pawn_y))
+                .is_some_and(|piece| piece.piece_type == PieceType::Pawn && piece.color == color)
+        });
+        if can_capture {
+            result ^= RANDOM64[EN_PASSANT_OFFSET + x as usize];
+        }
+    }
+    if color == PieceColor::White {
+        result ^= RANDOM64[TURN_OFFSET];
+    }
+    result
+}
+
+/** The move of a given Polyglot move code in the current position of a given game, if possible. The
+castling moves are coded as the king capturing its own rook, e.g. e1h1 instead of e1g1. */
+fn decode_move(game: &ChessGame, raw_move: u16) -> Option<(Move, Option<PieceType>)> {
+    let square = |file: u16, row: u16| {
+        format!(
+            "{}{}",
+            (b'a' + file as u8) as char,
+            (b'1' + row as u8) as char
+        )
+    };
+    let (to_file, to_row) = (raw_move & 7, (raw_move >> 3) & 7);
+    let (from_file, from_row) = ((raw_move >> 6) & 7, (raw_move >> 9) & 7);
+    let promotion = match (raw_move >> 12) & 7 {
+        1 => "n",
+        2 => "b",
+        3 => "r",
+        4 => "q",
+        _ => "",
+    };
+    let is_king = game
+        .piece_at((from_file as i8, 7 - from_row as i8))
+        .is_some_and(|piece| piece.piece_type == PieceType::King);
+    let to_file = match (is_king, from_file, to_file, from_row == to_row) {
+        (true, 4, 7, true) => 6,
+        (true, 4, 0, true) => 2,
+        _ => to_file,
+    };
+    let coordinates = format!(
+        "{}{}{}",
+        square(from_file, from_row),
+        square(to_file, to_row),
+        promotion
+    );
+    game.parse_move(&coordinates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::game::notation::coordinate_notation;
+
+    fn key_after(moves: &str) -> u64 {
+        let mut game = ChessGame::new();
+        for text in moves.split_whitespace() {
+            let (chess_move, _) = game.parse_move(text).unwrap();
+            game.execute_move(&chess_move);
+        }
+        polyglot_key(&game)
+    }
+
+    #[test]
+    fn keys_match_the_polyglot_specification() {
+        assert_eq!(key_after(""), 0x463b96181691fc9c);
+        assert_eq!(key_after("e4"), 0x823c9b50fd114196);
+        assert_eq!(key_after("e4 d5"), 0x0756b94461c50fb0);
+        assert_eq!(key_after("e4 d5 e5"), 0x662fafb965db29d4);
+        // The en passant capture exf6 is possible.
+        assert_eq!(key_after("e4 d5 e5 f5"), 0x22a48b5a8e47ff78);
+        // The kings lose their castling rights.
+        assert_eq!(key_after("e4 d5 e5 f5 Ke2"), 0x652a607ca3f242c1);
+        assert_eq!(key_after("e4 d5 e5 f5 Ke2 Kf7"), 0x00fdd303c946bdd9);
+        assert_eq!(key_after("a4 b5 h4 b4 c4"), 0x3c8123ea7b067637);
+        assert_eq!(key_after("a4 b5 h4 b4 c4 bxc3 Ra3"), 0x5c3f9b829b279560);
+    }
+
+    #[test]
+    fn castling_is_decoded_from_the_king_capturing_its_rook() {
+        let game = ChessGame::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let key = polyglot_key(&game);
+        // e1h1 and e1a1 with the files in bits 0-2 and 6-8, each followed by weight 1.
+        let mut bytes = Vec::new();
+        for raw_move in [4 << 6 | 7, 4 << 6] {
+            bytes.extend(key.to_be_bytes());
+            bytes.extend(u16::to_be_bytes(raw_move));
+            bytes.extend([0, 1, 0, 0, 0, 0]);
+        }
+        let book = OpeningBook::from_bytes(&bytes).unwrap();
+        let mut moves: Vec<String> = book
+            .moves(&game)
+            .iter()
+            .map(|book_move| coordinate_notation(&book_move.chess_move, book_move.promotion))
+            .collect();
+        moves.sort();
+        assert_eq!(moves, ["e1c1", "e1g1"]);
+    }
+}
@@ -13,6 +13,12 @@ pub struct Chessboard {
     squares: Vec<BoardSquare>,
 }
 
+impl Default for Chessboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Chessboard {
     pub fn new() -> Self {
         let mut squares = Vec::new();
@@ -75,6 +81,17 @@ impl BoardSquare {
     pub fn new(row: char, column: char) -> Self {
         Self { row, column }
     }
+    /** The square denoted by a given algebraic notation, e.g. "e4". None if invalid. */
+    pub fn from_notation(notation: &str) -> Option<Self> {
+        let mut chars = notation.chars();
+        let column = chars.next()?;
+        let row = chars.next()?;
+        match ('a'..='h').contains(&column) && ('1'..='8').contains(&row) && chars.next().is_none()
+        {
+            true => Some(Self::new(row, column)),
+            false => None,
+        }
+    }
 
     /** x position of this square. Depending on the column, e.g. column 'a' is x=0. */
     pub fn x(&self) -> i8 {
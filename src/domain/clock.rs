@@ -0,0 +1,102 @@
+use std::fmt::{Display, Formatter};
+
+use crate::domain::pieces::PieceColor;
+
+/** The time each player has for the whole game together with the time added after each move. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeControl {
+    pub initial_secs: u32,
+    pub increment_secs: u32,
+}
+
+impl TimeControl {
+    /** Parse a time control in the form "minutes+increment", e.g. "5+3" for five minutes and three
+    seconds per move. The increment may be omitted. */
+    pub fn parse(text: &str) -> Result<TimeControl, String> {
+        let error = || format!("Invalid time control '{}', expected e.g. \"5+3\"", text);
+        let (minutes, increment) = text.trim().split_once('+').unwrap_or((text.trim(), "0"));
+        let minutes: f32 = minutes.parse().map_err(|_| error())?;
+        let increment_secs: u32 = increment.parse().map_err(|_| error())?;
+        if !minutes.is_finite() || minutes <= 0.0 {
+            return Err(error());
+        }
+        Ok(TimeControl {
+            initial_secs: (minutes * 60.0).round() as u32,
+            increment_secs,
+        })
+    }
+}
+
+impl Display for TimeControl {
+    /** The time control in the form "minutes+increment", e.g. "5+3" or "0.5+0". */
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let minutes = self.initial_secs as f32 / 60.0;
+        write!(f, "{}+{}", minutes, self.increment_secs)
+    }
+}
+
+/** A chess clock measuring the remaining time of both players of a game. */
+#[derive(Debug, Clone)]
+pub struct ChessClock {
+    time_control: TimeControl,
+    white_ms: f32,
+    black_ms: f32,
+    move_count: usize,
+}
+
+impl ChessClock {
+    pub fn new(time_control: TimeControl) -> Self {
+        let initial_ms = time_control.initial_secs as f32 * 1000.0;
+        Self {
+            time_control,
+            white_ms: initial_ms,
+            black_ms: initial_ms,
+            move_count: 0,
+        }
+    }
+
+    /** The remaining time of a given color in milliseconds. */
+    pub fn remaining_ms(&self, color: PieceColor) -> f32 {
+        match color {
+            PieceColor::White => self.white_ms,
+            PieceColor::Black => self.black_ms,
+        }
+    }
+    /** The color whose time has run out, if any. */
+    pub fn flagged(&self) -> Option<PieceColor> {
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find(|color| self.remaining_ms(*color) <= 0.0)
+    }
+
+    /** Let a given number of milliseconds pass for a given color to move in a game with a given
+    number of moves (plies). Each move made since the last call adds the increment to the time of
+    the color which made it. The clock starts with the first move and stops when a time is up. */
+    pub fn advance(&mut self, elapsed_ms: f32, color: PieceColor, move_count: usize) {
+        if self.flagged().is_some() {
+            return;
+        }
+        let increment_ms = self.time_control.increment_secs as f32 * 1000.0;
+        let new_moves = move_count.saturating_sub(self.move_count);
+        (0..new_moves).for_each(|index| {
+            // The last move was made by the opponent of the color to move, the one before by it.
+            let mover = match (new_moves - index) % 2 {
+                1 => color.opponent(),
+                _ => color,
+            };
+            *self.remaining_ms_mut(mover) += increment_ms;
+        });
+        self.move_count = move_count;
+        if move_count > 0 {
+            let remaining = self.remaining_ms_mut(color);
+            *remaining = (*remaining - elapsed_ms).max(0.0);
+        }
+    }
+
+    fn remaining_ms_mut(&mut self, color: PieceColor) -> &mut f32 {
+        match color {
+            PieceColor::White => &mut self.white_ms,
+            PieceColor::Black => &mut self.black_ms,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::domain::chessboard::BoardSquare;
+use crate::domain::game::ChessGame;
+use crate::domain::pieces::PieceType;
+
+/** The table of openings: One line per opening with its ECO code, its name, its moves in Standard
+Algebraic Notation and the key of the position they reach, separated by tabs. The positions are
+given so the moves need not be played to look up an opening.
+See: [position_key] */
+const OPENINGS: &str = include_str!("openings.tsv");
+
+/** An opening of the Encyclopaedia of Chess Openings (ECO), e.g. "C65 Ruy Lopez: Berlin Defence". */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opening {
+    /** The code of the opening, e.g. "C65". */
+    pub code: &'static str,
+    /** The name of the opening, e.g. "Ruy Lopez: Berlin Defence". */
+    pub name: &'static str,
+}
+
+impl fmt::Display for Opening {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code, self.name)
+    }
+}
+
+/** The opening of the current position of a given game: The opening of the latest position on the way
+to it which is reached by an opening of the table. Since positions are compared instead of moves, an
+opening is also recognized if it is reached by another order of moves. None if no position of the
+game is an opening of the table. */
+pub fn classify(game: &ChessGame) -> Option<Opening> {
+    let openings = openings();
+    let mut position = game.initial_position();
+    let mut result = openings.get(position_key(&position).as_str()).copied();
+    for executed_move in &game.executed_moves() {
+        position.replay_move(executed_move);
+        if let Some(opening) = openings.get(position_key(&position).as_str()) {
+            result = Some(*opening);
+        }
+    }
+    result
+}
+
+/** The openings of the table by the key of the position they reach, built on first use. */
+fn openings() -> &'static HashMap<&'static str, Opening> {
+    static OPENINGS_BY_POSITION: OnceLock<HashMap<&'static str, Opening>> = OnceLock::new();
+    OPENINGS_BY_POSITION.get_or_init(|| {
+        OPENINGS
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&'static str> = line.split('\t').collect();
+                match fields[..] {
+                    [code, name, _, position] => Some((position, Opening { code, name })),
+                    _ => None,
+                }
+            })
+            .collect()
+    })
+}
+
+/** The key of the current position of a given game: Its FEN without the move counters, so the same
+position reached by another order of moves has the same key. Like in a Polyglot key, the en passant
+square only counts if a pawn of the color to move can capture there, so 1. d4 d5 2. c4 and
+1. c4 d5 2. d4 have the same key. */
+fn position_key(game: &ChessGame) -> String {
+    let fen = game.fen();
+    let mut fields: Vec<&str> = fen.split_whitespace().take(4).collect();
+    let can_capture = BoardSquare::from_notation(fields[3]).is_some_and(|target| {
+        game.possible_move_choices().iter().any(|(chess_move, _)| {
+            chess_move.piece.piece_type == PieceType::Pawn && chess_move.target == target
+        })
+    });
+    if !can_capture {
+        fields[3] = "-";
+    }
+    fields.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opening_after(moves: &str) -> Option<Opening> {
+        let mut game = ChessGame::new();
+        for text in moves.split_whitespace() {
+            let (chess_move, _) = game.parse_move(text).unwrap();
+            game.execute_move(&chess_move);
+        }
+        classify(&game)
+    }
+
+    #[test]
+    fn openings_are_recognized_after_a_transposition() {
+        let code = |moves| opening_after(moves).map(|opening| opening.code);
+        assert_eq!(code("d4 d5 c4"), Some("D06"));
+        assert_eq!(code("c4 d5 d4"), Some("D06"));
+        assert_eq!(code("c4"), Some("A10"));
+        assert_eq!(opening_after(""), None);
+    }
+
+    #[test]
+    fn positions_of_the_table_are_keys() {
+        for line in OPENINGS.lines() {
+            let position = line.split('\t').nth(3).unwrap();
+            let game = ChessGame::from_fen(&format!("{} 0 1", position)).unwrap();
+            assert_eq!(position_key(&game), position);
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use std::fmt::{Display, Formatter};
+
+use crate::domain::game::ChessGame;
+use crate::domain::pieces::{Piece, PieceColor, PieceType};
+
+/** The game phase of the start position, counting each minor piece 1, rook 2 and queen 4. The
+phase decreases to 0 in the endgame as pieces are captured. */
+const MAX_PHASE: i32 = 24;
+
+/** The middlegame and endgame values of the piece types in centipawns. */
+const MATERIAL: [(PieceType, Weight); 6] = [
+    (PieceType::Pawn, Weight(100, 110)),
+    (PieceType::Knight, Weight(320, 300)),
+    (PieceType::Bishop, Weight(330, 310)),
+    (PieceType::Rook, Weight(500, 520)),
+    (PieceType::Queen, Weight(950, 920)),
+    (PieceType::King, Weight(0, 0)),
+];
+
+const DOUBLED_PAWN: Weight = Weight(-10, -20);
+const ISOLATED_PAWN: Weight = Weight(-10, -15);
+/** The bonus of a passed pawn by its rank, from the 2nd to the 7th rank. */
+const PASSED_PAWN: [Weight; 6] = [
+    Weight(5, 10),
+    Weight(10, 20),
+    Weight(20, 40),
+    Weight(35, 70),
+    Weight(60, 120),
+    Weight(100, 200),
+];
+const BISHOP_PAIR: Weight = Weight(30, 50);
+/** The bonus of a pawn right in front of the king and one rank further. */
+const PAWN_SHIELD: [i32; 2] = [10, 5];
+/** The penalty of a file next to or of the king without an own pawn. */
+const OPEN_KING_FILE: i32 = -15;
+/** The bonus of each possible move of a piece type beyond a typical number of moves. */
+const MOBILITY: [(PieceType, i32, Weight); 4] = [
+    (PieceType::Knight, 4, Weight(4, 4)),
+    (PieceType::Bishop, 6, Weight(5, 5)),
+    (PieceType::Rook, 7, Weight(2, 4)),
+    (PieceType::Queen, 13, Weight(1, 2)),
+];
+
+/** The piece-square tables from White's point of view, the first row is the 8th rank. */
+#[rustfmt::skip]
+const PAWN_SQUARES: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+#[rustfmt::skip]
+const KNIGHT_SQUARES: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+#[rustfmt::skip]
+const BISHOP_SQUARES: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+#[rustfmt::skip]
+const ROOK_SQUARES: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+#[rustfmt::skip]
+const QUEEN_SQUARES: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+/** The king hides behind its pawns in the middlegame. */
+#[rustfmt::skip]
+const KING_MIDDLEGAME_SQUARES: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+/** The king becomes an active piece in the endgame. */
+#[rustfmt::skip]
+const KING_ENDGAME_SQUARES: [i32; 64] = [
+    -50,-40,-30,-20,-20,-30,-40,-50,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+/** A value in the middlegame and in the endgame, which is blended by the game phase. */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Weight(i32, i32);
+
+impl Weight {
+    fn times(&self, factor: i32) -> Weight {
+        Weight(self.0 * factor, self.1 * factor)
+    }
+    fn plus(&self, other: Weight) -> Weight {
+        Weight(self.0 + other.0, self.1 + other.1)
+    }
+    /** The value of a given game phase, from 0 for the endgame to the maximum for the middlegame. */
+    fn tapered(&self, phase: i32) -> i32 {
+        (self.0 * phase + self.1 * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+/** The static evaluation of a position without searching any moves ahead, broken down into its
+terms. All terms are in centipawns from White's point of view, i.e. positive values are good for
+White. Each term blends its middlegame and endgame value by the game phase. */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Evaluation {
+    pub material: i32,
+    pub piece_squares: i32,
+    /** Doubled and isolated pawns as well as passed pawns. */
+    pub pawn_structure: i32,
+    /** The pawns in front of the king and open files next to it, in the middlegame only. */
+    pub king_safety: i32,
+    /** The number of possible moves of the knights, bishops, rooks and queens. */
+    pub mobility: i32,
+    pub bishop_pair: i32,
+    /** The game phase from 0 for a pure pawn endgame to 24 for all pieces on the board. */
+    pub phase: i32,
+}
+
+impl Evaluation {
+    /** The evaluation of the current position of a given game. */
+    pub fn of(game: &ChessGame) -> Evaluation {
+        let phase = game
+            .pieces
+            .iter()
+            .map(|piece| match piece.piece_type {
+                PieceType::Knight | PieceType::Bishop => 1,
+                PieceType::Rook => 2,
+                PieceType::Queen => 4,
+                PieceType::King | PieceType::Pawn => 0,
+            })
+            .sum::<i32>()
+            .min(MAX_PHASE);
+        let term = |evaluate: &dyn Fn(&ChessGame, PieceColor) -> Weight| {
+            let weight =
+                evaluate(game, PieceColor::White).plus(evaluate(game, PieceColor::Black).times(-1));
+            weight.tapered(phase)
+        };
+        Evaluation {
+            material: term(&material),
+            piece_squares: term(&piece_squares),
+            pawn_structure: term(&pawn_structure),
+            king_safety: term(&king_safety),
+            mobility: term(&mobility),
+            bishop_pair: term(&bishop_pair),
+            phase,
+        }
+    }
+
+    /** The sum of all terms in centipawns from White's point of view. */
+    pub fn total(&self) -> i32 {
+        self.terms().iter().map(|(_, value)| value).sum()
+    }
+    /** The sum of all terms in centipawns from the point of view of a given color. */
+    pub fn total_for(&self, color: PieceColor) -> i32 {
+        match color {
+            PieceColor::White => self.total(),
+            PieceColor::Black => -self.total(),
+        }
+    }
+
+    /** The names and values of all terms, e.g. to show them. */
+    pub fn terms(&self) -> Vec<(&'static str, i32)> {
+        vec![
+            ("Material", self.material),
+            ("Piece squares", self.piece_squares),
+            ("Pawn structure", self.pawn_structure),
+            ("King safety", self.king_safety),
+            ("Mobility", self.mobility),
+            ("Bishop pair", self.bishop_pair),
+        ]
+    }
+}
+
+impl Display for Evaluation {
+    /** One line per term with its value in pawns, followed by the total and the game phase. */
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in self.terms() {
+            writeln!(f, "{:<15}{:>+7.2}", name, value as f64 / 100.0)?;
+        }
+        writeln!(f, "{:<15}{:>+7.2}", "Total", self.total() as f64 / 100.0)?;
+        write!(f, "{:<15}{:>4}/{}", "Phase", self.phase, MAX_PHASE)
+    }
+}
+
+fn pieces_of(game: &ChessGame, color: PieceColor) -> impl Iterator<Item = &Piece> {
+    game.pieces.iter().filter(move |piece| piece.color == color)
+}
+
+fn material(game: &ChessGame, color: PieceColor) -> Weight {
+    pieces_of(game, color).fold(Weight::default(), |sum, piece| {
+        let (_, weight) = MATERIAL
+            .iter()
+            .find(|(piece_type, _)| *piece_type == piece.piece_type)
+            .unwrap();
+        sum.plus(*weight)
+    })
+}
+
+fn piece_squares(game: &ChessGame, color: PieceColor) -> Weight {
+    pieces_of(game, color).fold(Weight::default(), |sum, piece| {
+        let (x, y) = piece.square.position();
+        // The tables of Black are the ones of White mirrored vertically.
+        let rank_index = match color {
+            PieceColor::White => y,
+            PieceColor::Black => 7 - y,
+        };
+        let index = (rank_index * 8 + x) as usize;
+        let weight = match piece.piece_type {
+            PieceType::Pawn => Weight(PAWN_SQUARES[index], PAWN_SQUARES[index] / 2),
+            PieceType::Knight => Weight(KNIGHT_SQUARES[index], KNIGHT_SQUARES[index]),
+            PieceType::Bishop => Weight(BISHOP_SQUARES[index], BISHOP_SQUARES[index]),
+            PieceType::Rook => Weight(ROOK_SQUARES[index], ROOK_SQUARES[index]),
+            PieceType::Queen => Weight(QUEEN_SQUARES[index], QUEEN_SQUARES[index]),
+            PieceType::King => Weight(KING_MIDDLEGAME_SQUARES[index], KING_ENDGAME_SQUARES[index]),
+        };
+        sum.plus(weight)
+    })
+}
+
+/** The files (x) and ranks counted from the own side (0 to 7) of the pawns of a given color. */
+fn pawns(game: &ChessGame, color: PieceColor) -> Vec<(i8, i8)> {
+    pieces_of(game, color)
+        .filter(|piece| piece.piece_type == PieceType::Pawn)
+        .map(relative_position)
+        .collect()
+}
+
+/** The file (x) and the rank counted from the own side (0 to 7) of a given piece. */
+fn relative_position(piece: &Piece) -> (i8, i8) {
+    let (x, y) = piece.square.position();
+    match piece.color {
+        PieceColor::White => (x, 7 - y),
+        PieceColor::Black => (x, y),
+    }
+}
+
+fn pawn_structure(game: &ChessGame, color: PieceColor) -> Weight {
+    let own = pawns(game, color);
+    // The ranks of the opponent's pawns counted from the own side.
+    let opponents: Vec<(i8, i8)> = pawns(game, color.opponent())
+        .into_iter()
+        .map(|(x, rank)| (x, 7 - rank))
+        .collect();
+    let mut result = Weight::default();
+    for file in 0..8 {
+        let count = own.iter().filter(|(x, _)| *x == file).count() as i32;
+        if count > 1 {
+            result = result.plus(DOUBLED_PAWN.times(count - 1));
+        }
+        let has_neighbor = own.iter().any(|(x, _)| (x - file).abs() == 1);
+        if count > 0 && !has_neighbor {
+            result = result.plus(ISOLATED_PAWN.times(count));
+        }
+    }
+    for (file, rank) in &own {
+        let is_passed = !opponents
+            .iter()
+            .any(|(x, opponent_rank)| (x - file).abs() <= 1 && opponent_rank > rank);
+        if is_passed {
+            result = result.plus(PASSED_PAWN[(*rank - 1).clamp(0, 5) as usize]);
+        }
+    }
+    result
+}
+
+fn king_safety(game: &ChessGame, color: PieceColor) -> Weight {
+    let Some(king) = pieces_of(game, color).find(|piece| piece.piece_type == PieceType::King)
+    else {
+        return Weight::default();
+    };
+    let (king_file, king_rank) = relative_position(king);
+    let own = pawns(game, color);
+    let mut score = 0;
+    for file in (king_file - 1).max(0)..=(king_file + 1).min(7) {
+        let shield = own
+            .iter()
+            .filter(|(x, _)| *x == file)
+            .filter_map(|(_, rank)| match rank - king_rank {
+                1 => Some(PAWN_SHIELD[0]),
+                2 => Some(PAWN_SHIELD[1]),
+                _ => None,
+            })
+            .max();
+        score += shield.unwrap_or(0);
+        if !own.iter().any(|(x, _)| *x == file) {
+            score += OPEN_KING_FILE;
+        }
+    }
+    Weight(score, 0)
+}
+
+fn mobility(game: &ChessGame, color: PieceColor) -> Weight {
+    pieces_of(game, color).fold(Weight::default(), |sum, piece| {
+        match MOBILITY
+            .iter()
+            .find(|(piece_type, _, _)| *piece_type == piece.piece_type)
+        {
+            Some((_, typical_moves, weight)) => {
+                let moves = game.possible_moves(piece).len() as i32;
+                sum.plus(weight.times(moves - typical_moves))
+            }
+            None => sum,
+        }
+    })
+}
+
+fn bishop_pair(game: &ChessGame, color: PieceColor) -> Weight {
+    let bishops = pieces_of(game, color)
+        .filter(|piece| piece.piece_type == PieceType::Bishop)
+        .count();
+    match bishops >= 2 {
+        true => BISHOP_PAIR,
+        false => Weight::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /** The FEN of a given one with the colors swapped: The board mirrored vertically, the other
+    color to move and the castling rights of the other color. */
+    fn mirrored_fen(fen: &str) -> String {
+        let swap_case = |text: &str| -> String {
+            text.chars()
+                .map(|c| match c.is_ascii_uppercase() {
+                    true => c.to_ascii_lowercase(),
+                    false => c.to_ascii_uppercase(),
+                })
+                .collect()
+        };
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        let ranks: Vec<&str> = fields[0].split('/').rev().collect();
+        let color = match fields[1] {
+            "w" => "b",
+            _ => "w",
+        };
+        let castling = match fields[2] {
+            "-" => "-".to_string(),
+            rights => {
+                let mut swapped: Vec<char> = swap_case(rights).chars().collect();
+                swapped.sort_by_key(|c| (c.is_ascii_lowercase(), *c));
+                swapped.into_iter().collect()
+            }
+        };
+        format!(
+            "{} {} {} - 0 1",
+            swap_case(&ranks.join("/")),
+            color,
+            castling
+        )
+    }
+
+    #[test]
+    fn start_position_is_equal() {
+        let evaluation = Evaluation::of(&ChessGame::new());
+        assert_eq!(evaluation.total(), 0);
+        assert!(evaluation.terms().iter().all(|(_, value)| *value == 0));
+        assert_eq!(evaluation.phase, MAX_PHASE);
+    }
+
+    #[test]
+    fn mirrored_positions_have_opposite_evaluations() {
+        let fens = [
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+            "r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQK2R w KQkq - 0 5",
+            "2r3k1/pp3ppp/4p3/3pP3/1P1P4/P4N2/5PPP/2R3K1 w - - 0 24",
+            "8/5k2/3p4/2pP4/2P5/4K3/8/8 w - - 0 50",
+            "r3k2r/1b3ppp/p1n1pn2/1p6/3P4/2NB1N2/PP3PPP/R1B2RK1 b kq - 3 12",
+        ];
+        for fen in fens {
+            let evaluation = Evaluation::of(&ChessGame::from_fen(fen).unwrap());
+            let mirrored = Evaluation::of(&ChessGame::from_fen(&mirrored_fen(fen)).unwrap());
+            assert_eq!(evaluation.total() + mirrored.total(), 0, "{}", fen);
+            for ((name, value), (_, mirrored_value)) in
+                evaluation.terms().into_iter().zip(mirrored.terms())
+            {
+                assert_eq!(value + mirrored_value, 0, "{} of {}", name, fen);
+            }
+            assert_eq!(evaluation.phase, mirrored.phase, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn extra_material_is_good_for_its_color() {
+        let white_queen = "4k3/8/8/8/8/8/8/3QK3 w - - 0 1";
+        let evaluation = Evaluation::of(&ChessGame::from_fen(white_queen).unwrap());
+        assert!(evaluation.material > 0);
+        assert!(evaluation.total_for(PieceColor::White) > 0);
+        assert!(evaluation.total_for(PieceColor::Black) < 0);
+    }
+}
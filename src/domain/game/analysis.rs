@@ -11,6 +11,15 @@ impl ChessGame {
             .any(|piece| piece.color == *color && !self.possible_moves(piece).is_empty())
     }
 
+    /** All possible moves all the pieces of a given color can currently make in this [ChessGame]. */
+    pub fn all_possible_moves(&self, color: &PieceColor) -> Vec<Move> {
+        self.pieces
+            .iter()
+            .filter(|piece| piece.color == *color)
+            .flat_map(|piece| self.possible_moves(piece))
+            .collect()
+    }
+
     /** All possible moves a given piece can currently make in this [ChessGame]. */
     pub fn possible_moves(&self, piece: &Piece) -> Vec<Move> {
         if self.promotion_pawn().is_some() {
@@ -141,7 +150,7 @@ impl ChessGame {
 
     /** Return a clone of the game where a given move is executed without affecting the original game, */
     pub fn calculate_move(&self, chess_move: &Move) -> ChessGame {
-        let mut result = self.clone();
+        let mut result = self.position_copy();
         result.execute_move(&chess_move);
         result
     }
@@ -152,12 +161,20 @@ impl ChessGame {
             .is_check_color(chess_move.piece.color)
     }
 
+    /** Whether the color to move next is checkmated. Never while a pawn waits for its promotion piece,
+    since no move is possible before. */
     pub fn is_check_mate(&self) -> bool {
         let next_color = self.next_move_color();
-        self.is_check_color(next_color) && !self.can_move(&next_color)
+        self.promotion_pawn.is_none()
+            && self.is_check_color(next_color)
+            && !self.can_move(&next_color)
     }
+    /** Whether the color to move next is stalemated. Never while a pawn waits for its promotion piece,
+    since no move is possible before. */
     pub fn is_stalemate(&self) -> bool {
         let next_color = self.next_move_color();
-        !self.is_check_color(next_color) && !self.can_move(&next_color)
+        self.promotion_pawn.is_none()
+            && !self.is_check_color(next_color)
+            && !self.can_move(&next_color)
     }
 }
@@ -0,0 +1,210 @@
+use crate::domain::chessboard::BoardSquare;
+
+/** Numeric Annotation Glyphs (NAG) together with their common symbols. */
+const NAG_SYMBOLS: [(u8, &str); 14] = [
+    (1, "!"),
+    (2, "?"),
+    (3, "!!"),
+    (4, "??"),
+    (5, "!?"),
+    (6, "?!"),
+    (10, "="),
+    (13, "~"),
+    (14, "+="),
+    (15, "=+"),
+    (16, "+/-"),
+    (17, "-/+"),
+    (18, "+-"),
+    (19, "-+"),
+];
+
+/** The symbol of a given Numeric Annotation Glyph, e.g. "!?" for 5. A generic "$n" if the glyph has
+no common symbol. */
+pub fn nag_symbol(nag: u8) -> String {
+    match NAG_SYMBOLS.iter().find(|(number, _)| *number == nag) {
+        Some((_, symbol)) => symbol.to_string(),
+        None => format!("${}", nag),
+    }
+}
+
+/** The Numeric Annotation Glyph denoted by a given symbol, e.g. 1 for "!". */
+pub fn nag_of_symbol(symbol: &str) -> Option<u8> {
+    NAG_SYMBOLS
+        .iter()
+        .find(|(_, nag_symbol)| *nag_symbol == symbol)
+        .map(|(number, _)| *number)
+}
+
+/** The colors available for arrows and highlighted squares. */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MarkerColor {
+    Green,
+    Red,
+    Yellow,
+    Blue,
+}
+
+impl MarkerColor {
+    /** The letter used for this color in the PGN commands `[%cal]` and `[%csl]`. */
+    pub fn letter(&self) -> char {
+        match self {
+            MarkerColor::Green => 'G',
+            MarkerColor::Red => 'R',
+            MarkerColor::Yellow => 'Y',
+            MarkerColor::Blue => 'B',
+        }
+    }
+    pub fn from_letter(letter: char) -> Option<MarkerColor> {
+        match letter {
+            'G' => Some(MarkerColor::Green),
+            'R' => Some(MarkerColor::Red),
+            'Y' => Some(MarkerColor::Yellow),
+            'B' => Some(MarkerColor::Blue),
+            _ => None,
+        }
+    }
+}
+
+/** An arrow drawn from one square of the board to another. */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Arrow {
+    pub color: MarkerColor,
+    pub from: BoardSquare,
+    pub to: BoardSquare,
+}
+
+/** A single highlighted square of the board. */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SquareMarker {
+    pub color: MarkerColor,
+    pub square: BoardSquare,
+}
+
+/** The annotation of a single position of a game: A text comment, Numeric Annotation Glyphs (NAG),
+arrows and highlighted squares. */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Annotation {
+    pub comment: String,
+    pub nags: Vec<u8>,
+    pub arrows: Vec<Arrow>,
+    pub squares: Vec<SquareMarker>,
+    /** Other commands of the PGN comment, e.g. `[%clk 0:05:00]`, which are kept as they are. */
+    pub commands: Vec<String>,
+}
+
+impl Annotation {
+    /** The symbols of all the glyphs of this annotation, e.g. "!?". */
+    pub fn nag_symbols(&self) -> String {
+        self.nags.iter().map(|nag| nag_symbol(*nag)).collect()
+    }
+
+    /** Add a given glyph, or remove it if it's already present. */
+    pub fn toggle_nag(&mut self, nag: u8) {
+        match self.nags.contains(&nag) {
+            true => self.nags.retain(|present| *present != nag),
+            false => self.nags.push(nag),
+        }
+    }
+    /** Add an arrow between two squares, or remove it if there already is one. */
+    pub fn toggle_arrow(&mut self, arrow: Arrow) {
+        let present = |other: &Arrow| other.from == arrow.from && other.to == arrow.to;
+        match self.arrows.iter().any(present) {
+            true => self.arrows.retain(|other| !present(other)),
+            false => self.arrows.push(arrow),
+        }
+    }
+    /** Highlight a square, or remove the highlight if it is already highlighted. */
+    pub fn toggle_square(&mut self, marker: SquareMarker) {
+        match self
+            .squares
+            .iter()
+            .any(|other| other.square == marker.square)
+        {
+            true => self.squares.retain(|other| other.square != marker.square),
+            false => self.squares.push(marker),
+        }
+    }
+
+    /** Add the text of a PGN comment to this annotation. The commands `[%cal ...]` and `[%csl ...]`
+    are converted to arrows and highlighted squares, other commands are kept as they are, and all
+    other text is added to the comment. */
+    pub fn add_pgn_comment(&mut self, pgn_comment: &str) {
+        let mut text = pgn_comment.to_string();
+        while let Some(start) = text.find("[%") {
+            let Some(length) = text[start..].find(']') else {
+                break;
+            };
+            let command = text[start + 2..start + length].to_string();
+            let verbatim = text[start..=start + length].to_string();
+            text.replace_range(start..=start + length, "");
+            match command.split_once(char::is_whitespace) {
+                Some(("cal", arguments)) => arguments
+                    .split(',')
+                    .filter_map(|argument| parse_arrow(argument.trim()))
+                    .for_each(|arrow| self.arrows.push(arrow)),
+                Some(("csl", arguments)) => arguments
+                    .split(',')
+                    .filter_map(|argument| parse_square_marker(argument.trim()))
+                    .for_each(|marker| self.squares.push(marker)),
+                _ => self.commands.push(verbatim),
+            }
+        }
+        let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if !text.is_empty() {
+            if !self.comment.is_empty() {
+                self.comment.push(' ');
+            }
+            self.comment.push_str(&text);
+        }
+    }
+
+    /** The PGN comment text of this annotation including the commands for arrows and highlighted
+    squares and the other commands, but without the braces. None if there is nothing to comment. */
+    pub fn pgn_comment(&self) -> Option<String> {
+        let mut result = String::new();
+        if !self.squares.is_empty() {
+            let markers: Vec<String> = self
+                .squares
+                .iter()
+                .map(|marker| format!("{}{}", marker.color.letter(), marker.square))
+                .collect();
+            result.push_str(&format!("[%csl {}]", markers.join(",")));
+        }
+        if !self.arrows.is_empty() {
+            let arrows: Vec<String> = self
+                .arrows
+                .iter()
+                .map(|arrow| format!("{}{}{}", arrow.color.letter(), arrow.from, arrow.to))
+                .collect();
+            result.push_str(&format!("[%cal {}]", arrows.join(",")));
+        }
+        self.commands
+            .iter()
+            .for_each(|command| result.push_str(command));
+        if !self.comment.is_empty() {
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            result.push_str(&self.comment.replace('}', ")"));
+        }
+        match result.is_empty() {
+            true => None,
+            false => Some(result),
+        }
+    }
+}
+
+/** Parse an arrow of a `[%cal]` command, e.g. "Ge2e4". */
+pub fn parse_arrow(text: &str) -> Option<Arrow> {
+    let color = MarkerColor::from_letter(text.chars().next()?)?;
+    let from = BoardSquare::from_notation(text.get(1..3)?)?;
+    let to = BoardSquare::from_notation(text.get(3..)?)?;
+    Some(Arrow { color, from, to })
+}
+
+/** Parse a square marker of a `[%csl]` command, e.g. "Rd4". */
+pub fn parse_square_marker(text: &str) -> Option<SquareMarker> {
+    let color = MarkerColor::from_letter(text.chars().next()?)?;
+    let square = BoardSquare::from_notation(text.get(1..)?)?;
+    Some(SquareMarker { color, square })
+}
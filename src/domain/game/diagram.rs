@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter};
+
+use crate::domain::game::*;
+
+const EMPTY_SQUARE: char = '.';
+const EMPTY_GLYPH_SQUARE: char = '·';
+
+/** The parts of a text diagram of a game and how its pieces are shown. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiagramStyle {
+    /** Unicode chess symbols like '♘' instead of FEN letters like 'N'. */
+    pub unicode: bool,
+    /** Rank numbers left of and file letters below the board. */
+    pub coordinates: bool,
+    /** The color to move next and the FEN of the position below the board. */
+    pub footer: bool,
+    /** Black at the bottom of the board instead of White. */
+    pub flipped: bool,
+}
+
+impl Default for DiagramStyle {
+    fn default() -> Self {
+        Self {
+            unicode: false,
+            coordinates: true,
+            footer: true,
+            flipped: false,
+        }
+    }
+}
+
+impl ChessGame {
+    /** A text diagram of the current position of this game in a given style, one line per rank. */
+    pub fn diagram(&self, style: DiagramStyle) -> String {
+        // Rows run from rank 8 to 1 and columns from file a to h, both reversed when flipped.
+        let coords: Vec<i8> = match style.flipped {
+            false => (0..8).collect(),
+            true => (0..8).rev().collect(),
+        };
+        let mut lines: Vec<String> = coords
+            .iter()
+            .map(|y| {
+                let squares: Vec<String> = coords
+                    .iter()
+                    .map(|x| self.diagram_square((*x, *y), style.unicode).to_string())
+                    .collect();
+                match style.coordinates {
+                    true => format!("{} {}", 8 - y, squares.join(" ")),
+                    false => squares.join(" "),
+                }
+            })
+            .collect();
+        if style.coordinates {
+            let files: Vec<String> = coords
+                .iter()
+                .map(|x| ((b'a' + *x as u8) as char).to_string())
+                .collect();
+            lines.push(format!("  {}", files.join(" ")));
+        }
+        if style.footer {
+            lines.push(format!("{:?} to move", self.next_move_color()));
+            lines.push(self.fen());
+        }
+        lines.join("\n")
+    }
+
+    /** The position of this game in a single line: The ranks from 8 to 1 separated by '/' followed
+    by the color to move, e.g. "rnbqkbnr/pppppppp/......../......../....P.../......../PPPP.PPP/RNBQKBNR b"
+    after 1. e4. Unlike in FEN, every square has its own character. */
+    pub fn compact_diagram(&self) -> String {
+        let ranks: Vec<String> = (0..8)
+            .map(|y| (0..8).map(|x| self.diagram_square((x, y), false)).collect())
+            .collect();
+        let color = match self.next_move_color() {
+            PieceColor::White => 'w',
+            PieceColor::Black => 'b',
+        };
+        format!("{} {}", ranks.join("/"), color)
+    }
+
+    /** The character of the square at a given coordinate in a diagram. */
+    fn diagram_square(&self, coord: (i8, i8), unicode: bool) -> char {
+        match (self.piece_at(coord), unicode) {
+            (Some(piece), true) => piece.piece_type.glyph(piece.color),
+            (Some(piece), false) => piece.letter(),
+            (None, true) => EMPTY_GLYPH_SQUARE,
+            (None, false) => EMPTY_SQUARE,
+        }
+    }
+}
+
+impl Display for ChessGame {
+    /** A diagram of the current position with coordinates, the color to move and the FEN. The
+    alternate form, i.e. "{:#}", shows Unicode chess symbols instead of letters.
+    See: [ChessGame::diagram] */
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let style = DiagramStyle {
+            unicode: f.alternate(),
+            ..DiagramStyle::default()
+        };
+        write!(f, "{}", self.diagram(style))
+    }
+}
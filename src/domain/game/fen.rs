@@ -0,0 +1,259 @@
+use crate::domain::game::*;
+
+/** The Forsyth-Edwards Notation (FEN) of the standard start position. */
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+impl ChessGame {
+    /** A new game starting with the position of a given Forsyth-Edwards Notation (FEN), e.g.
+    "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1". The move counters may be
+    omitted. */
+    pub fn from_fen(fen: &str) -> Result<ChessGame, String> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if !(4..=6).contains(&fields.len()) {
+            return Err(format!("Expected 4 to 6 fields in FEN '{}'", fen));
+        }
+        let mut pieces = parse_placement(fields[0])?;
+        let color = match fields[1] {
+            "w" => PieceColor::White,
+            "b" => PieceColor::Black,
+            other => return Err(format!("Invalid color to move '{}'", other)),
+        };
+        let castling = fields[2];
+        if castling != "-" && !castling.chars().all(|c| "KQkq".contains(c)) {
+            return Err(format!("Invalid castling availability '{}'", castling));
+        }
+        pieces = pieces
+            .into_iter()
+            .map(|piece| with_fen_start_square(piece, castling))
+            .collect();
+        let counter = |index: usize, default: usize| match fields.get(index) {
+            Some(text) => text
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid move counter '{}'", text)),
+            None => Ok(default),
+        };
+        let mut game = ChessGame::new();
+        game.pieces = pieces.clone();
+        game.initial_pieces = pieces;
+        game.initial_color = color;
+        game.initial_halfmove_clock = counter(4, 0)?;
+        game.initial_fullmove_number = counter(5, 1)?.max(1);
+        game.initial_last_move = match fields[3] {
+            "-" => None,
+            square => Some(game.en_passant_victim_move(square, color)?),
+        };
+        if game.is_check_color(color.opponent()) {
+            return Err("The side not to move must not be in check".to_string());
+        }
+        game.chess_moves = game.calculate_check();
+        Ok(game)
+    }
+
+    /** The Forsyth-Edwards Notation (FEN) of the current position of this game. */
+    pub fn fen(&self) -> String {
+        let placement: Vec<String> = (0..8)
+            .map(|y| {
+                let mut rank = String::new();
+                let mut empty = 0;
+                for x in 0..8 {
+                    match self.piece_at((x, y)) {
+                        Some(piece) => {
+                            if empty > 0 {
+                                rank.push_str(&empty.to_string());
+                                empty = 0;
+                            }
+                            rank.push(piece.letter());
+                        }
+                        None => empty += 1,
+                    }
+                }
+                if empty > 0 {
+                    rank.push_str(&empty.to_string());
+                }
+                rank
+            })
+            .collect();
+        let color = match self.next_move_color() {
+            PieceColor::White => "w",
+            PieceColor::Black => "b",
+        };
+        let mut castling: String = [PieceColor::White, PieceColor::Black]
+            .iter()
+            .flat_map(|color| self.castling_availability(*color))
+            .collect();
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        let en_passant = match self.en_passant_target() {
+            Some(square) => square.to_string(),
+            None => "-".to_string(),
+        };
+        format!(
+            "{} {} {} {} {} {}",
+            placement.join("/"),
+            color,
+            castling,
+            en_passant,
+            self.halfmove_clock(),
+            self.fullmove_number()
+        )
+    }
+
+    /** The number of moves (plies) since the last capture or pawn move. */
+    pub fn halfmove_clock(&self) -> usize {
+        let reset = |executed_move: &ExecutedMove| {
+            executed_move.is_capture() || executed_move.piece.piece_type == PieceType::Pawn
+        };
+        match self.executed_moves.iter().rposition(reset) {
+            Some(index) => self.executed_moves.len() - index - 1,
+            None => self.initial_halfmove_clock + self.executed_moves.len(),
+        }
+    }
+
+    /** The castling availability of a given color in FEN, e.g. "KQ" for White or "q" for Black. */
+    fn castling_availability(&self, color: PieceColor) -> Vec<char> {
+        let home_rank = home_rank(color);
+        let unmoved_at = |piece_type: PieceType, x: i8| {
+            self.piece_at((x, home_rank)).is_some_and(|piece| {
+                piece.piece_type == piece_type
+                    && piece.color == color
+                    && !self.has_already_moved(piece)
+            })
+        };
+        let mut result = Vec::new();
+        if unmoved_at(PieceType::King, 4) {
+            if unmoved_at(PieceType::Rook, 7) {
+                result.push('K');
+            }
+            if unmoved_at(PieceType::Rook, 0) {
+                result.push('Q');
+            }
+        }
+        match color {
+            PieceColor::White => result,
+            PieceColor::Black => result.iter().map(|c| c.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /** The double step of a pawn which allows to capture it en passant at a given square of a
+    FEN, if a given color is to move. */
+    fn en_passant_victim_move(
+        &self,
+        square: &str,
+        color: PieceColor,
+    ) -> Result<ExecutedMove, String> {
+        let error = || format!("Invalid en passant target square '{}'", square);
+        let target = BoardSquare::from_notation(square).ok_or_else(error)?;
+        let direction = -pawn_direction(color);
+        let (x, y) = target.position();
+        let pawn = self
+            .piece_at((x, y + direction))
+            .filter(|pawn| pawn.piece_type == PieceType::Pawn && pawn.color != color)
+            .filter(|_| y == home_rank(color.opponent()) + 2 * pawn_direction(color.opponent()))
+            .ok_or_else(error)?;
+        let start = self
+            .board
+            .square_relative(target, (0, -direction))
+            .ok_or_else(error)?;
+        Ok(ExecutedMove::new(
+            pawn.with_start_square(*start),
+            *start,
+            pawn.square,
+            None,
+            false,
+        ))
+    }
+}
+
+/** The pieces of the piece placement field of a FEN. */
+fn parse_placement(placement: &str) -> Result<Vec<Piece>, String> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(format!(
+            "Expected 8 ranks in piece placement '{}'",
+            placement
+        ));
+    }
+    let mut result = Vec::new();
+    for (y, rank) in ranks.iter().enumerate() {
+        let mut x = 0;
+        for letter in rank.chars() {
+            if let Some(empty) = letter.to_digit(10) {
+                x += empty as usize;
+                continue;
+            }
+            let piece_type = PieceType::from_letter(letter.to_ascii_uppercase())
+                .ok_or_else(|| format!("Invalid piece letter '{}'", letter))?;
+            let color = match letter.is_ascii_uppercase() {
+                true => PieceColor::White,
+                false => PieceColor::Black,
+            };
+            if x >= 8 {
+                return Err(format!("Too many squares in rank '{}'", rank));
+            }
+            let square = BoardSquare::new((b'8' - y as u8) as char, (b'a' + x as u8) as char);
+            if piece_type == PieceType::Pawn && ["1", "8"].contains(&square.rank().as_str()) {
+                return Err(format!("Pawn on invalid square {}", square));
+            }
+            result.push(Piece::new(piece_type, color, square));
+            x += 1;
+        }
+        if x != 8 {
+            return Err(format!("Expected 8 squares in rank '{}'", rank));
+        }
+    }
+    for color in [PieceColor::White, PieceColor::Black] {
+        let kings = result
+            .iter()
+            .filter(|piece| piece.piece_type == PieceType::King && piece.color == color)
+            .count();
+        if kings != 1 {
+            return Err(format!("Expected exactly one {:?} King", color));
+        }
+    }
+    Ok(result)
+}
+
+/** A given piece of a FEN with the start square which reflects whether it has already moved. Pawns
+outside their home rank have moved, as well as Kings and Rooks without castling availability. */
+fn with_fen_start_square(piece: Piece, castling: &str) -> Piece {
+    let (x, y) = piece.square.position();
+    let home_rank = home_rank(piece.color);
+    let available = |letter: char| match piece.color {
+        PieceColor::White => castling.contains(letter),
+        PieceColor::Black => castling.contains(letter.to_ascii_lowercase()),
+    };
+    let has_moved = match piece.piece_type {
+        PieceType::Pawn => y != home_rank + pawn_direction(piece.color),
+        PieceType::King => y != home_rank || x != 4 || !(available('K') || available('Q')),
+        PieceType::Rook => match (x, y == home_rank) {
+            (7, true) => !available('K'),
+            (0, true) => !available('Q'),
+            _ => true,
+        },
+        _ => false,
+    };
+    match has_moved {
+        // Any other square will do; the mirrored one is guaranteed to be different.
+        true => piece.with_start_square(
+            BoardSquare::from_notation(&format!("{}{}", piece.square.file(), y + 1)).unwrap(),
+        ),
+        false => piece,
+    }
+}
+
+/** The y position of the rank on which the pieces of a given color start. */
+fn home_rank(color: PieceColor) -> i8 {
+    match color {
+        PieceColor::White => 7,
+        PieceColor::Black => 0,
+    }
+}
+
+/** The direction of the y position in which the pawns of a given color move. */
+fn pawn_direction(color: PieceColor) -> i8 {
+    match color {
+        PieceColor::White => -1,
+        PieceColor::Black => 1,
+    }
+}
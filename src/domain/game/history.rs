@@ -0,0 +1,107 @@
+use crate::domain::game::*;
+
+impl ChessGame {
+    /** A copy of the current position of this game with an empty move tree. Cheaper than a clone
+    when only the position is of interest, e.g. when calculating moves. */
+    pub(super) fn position_copy(&self) -> ChessGame {
+        Self {
+            board: self.board.clone(),
+            pieces: self.pieces.clone(),
+            initial_pieces: self.initial_pieces.clone(),
+            executed_moves: self.executed_moves.clone(),
+            chess_moves: self.chess_moves.clone(),
+            promotion_pawn: self.promotion_pawn,
+            initial_color: self.initial_color,
+            initial_fullmove_number: self.initial_fullmove_number,
+            initial_halfmove_clock: self.initial_halfmove_clock,
+            initial_last_move: self.initial_last_move.clone(),
+            move_tree: MoveTree::new(),
+            current_node: 0,
+        }
+    }
+
+    /** A new game which starts with the same pieces and color as this game, but without any moves. */
+    pub fn initial_position(&self) -> ChessGame {
+        let mut result = self.position_copy();
+        result.pieces = self.initial_pieces.clone();
+        result.executed_moves = Vec::new();
+        result.promotion_pawn = None;
+        result.chess_moves = result.calculate_check();
+        result
+    }
+
+    /** A new game which consists of the first given number of executed moves of this game.
+    The pieces are placed the way they were after the last of these moves. This game is not
+    affected. */
+    pub fn position_after(&self, ply: usize) -> ChessGame {
+        let mut result = self.initial_position();
+        self.executed_moves
+            .iter()
+            .take(ply)
+            .for_each(|executed_move| result.replay_move(executed_move));
+        result
+    }
+
+    /** Execute a given move which has already been executed in another game with the same
+    position, including its promotion. */
+    pub(crate) fn replay_move(&mut self, executed_move: &ExecutedMove) {
+        self.execute_move(&Move::new(executed_move.piece, executed_move.target_square));
+        if let Some(new_type) = executed_move.promotion {
+            self.exchange_promotion_pawn(new_type);
+        }
+    }
+
+    /** Set up the position of a given node of the move tree. The move tree itself is not changed,
+    so all moves after that node remain available. */
+    pub fn go_to_node(&mut self, node: NodeId) {
+        let mut position = self.initial_position();
+        self.move_tree.path(node).iter().for_each(|path_node| {
+            if let Some(executed_move) = &self.move_tree.node(*path_node).executed_move {
+                position.replay_move(executed_move);
+            }
+        });
+        self.restore_position(node, position);
+    }
+
+    /** A copy of the current position of this game without its move tree, to return to it later by
+    [ChessGame::restore_position] without replaying the moves. */
+    pub(crate) fn save_position(&self) -> ChessGame {
+        self.position_copy()
+    }
+
+    /** Make a given node of the move tree the current one with a given position, which must be the
+    position of that node, e.g. saved by [ChessGame::save_position]. */
+    pub(crate) fn restore_position(&mut self, node: NodeId, position: ChessGame) {
+        self.pieces = position.pieces;
+        self.executed_moves = position.executed_moves;
+        self.chess_moves = position.chess_moves;
+        self.promotion_pawn = position.promotion_pawn;
+        self.current_node = node;
+    }
+
+    /** Set up the position after a given number of moves of the current line. The following
+    moves remain available in the move tree, so a different move creates a new variation. */
+    pub fn go_to_ply(&mut self, ply: usize) {
+        let path = self.move_tree.path(self.current_node);
+        let node = match ply.min(path.len()) {
+            0 => self.move_tree.root(),
+            path_len => path[path_len - 1],
+        };
+        self.go_to_node(node);
+    }
+
+    /** Move the variation of the current position one position closer to the main line. */
+    pub fn promote_variation(&mut self) {
+        self.move_tree.promote_variation(self.current_node);
+    }
+    /** Move the variation of the current position one position away from the main line. */
+    pub fn demote_variation(&mut self) {
+        self.move_tree.demote_variation(self.current_node);
+    }
+    /** Delete the variation of the current position and go to the position where it started. Nothing
+    happens in the main line. */
+    pub fn delete_variation(&mut self) {
+        let node = self.move_tree.delete_variation(self.current_node);
+        self.go_to_node(node);
+    }
+}
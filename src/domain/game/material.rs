@@ -0,0 +1,27 @@
+use crate::domain::game::*;
+
+impl ChessGame {
+    /** All pieces of a given color captured so far, in the order they were captured. */
+    pub fn captured_pieces(&self, color: PieceColor) -> Vec<Piece> {
+        self.executed_moves
+            .iter()
+            .filter_map(|executed_move| executed_move.captured_piece)
+            .filter(|piece| piece.color == color)
+            .collect()
+    }
+
+    /** The total material value of all pieces of a given color on the board. */
+    pub fn material(&self, color: PieceColor) -> i32 {
+        self.pieces
+            .iter()
+            .filter(|piece| piece.color == color)
+            .map(|piece| piece.piece_type.value())
+            .sum()
+    }
+
+    /** The material advantage of a given color compared with its opponent. Negative if the
+    opponent has more material. */
+    pub fn material_advantage(&self, color: PieceColor) -> i32 {
+        self.material(color) - self.material(color.opponent())
+    }
+}
@@ -1,18 +1,39 @@
 use crate::domain::chessboard::*;
+use crate::domain::game::annotation::Annotation;
 use crate::domain::game::move_rules::*;
+use crate::domain::game::tree::*;
 use crate::domain::pieces::*;
 
 mod analysis;
+pub mod annotation;
+pub mod diagram;
+pub mod fen;
+mod history;
+mod material;
 mod move_rules;
+pub mod notation;
+pub mod tree;
 
 #[derive(Clone, Debug)]
 pub struct ChessGame {
     pub board: Chessboard,
     pub pieces: Vec<Piece>,
+    initial_pieces: Vec<Piece>,
     executed_moves: Vec<ExecutedMove>,
     chess_moves: Vec<Move>,
     promotion_pawn: Option<Piece>,
     initial_color: PieceColor,
+    initial_fullmove_number: usize,
+    initial_halfmove_clock: usize,
+    initial_last_move: Option<ExecutedMove>,
+    move_tree: MoveTree,
+    current_node: NodeId,
+}
+
+impl Default for ChessGame {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ChessGame {
@@ -21,10 +42,16 @@ impl ChessGame {
         Self {
             board: Chessboard::new(),
             pieces: create_start_positions(),
+            initial_pieces: create_start_positions(),
             executed_moves: vec![],
             chess_moves: vec![],
             promotion_pawn: None,
             initial_color: PieceColor::White,
+            initial_fullmove_number: 1,
+            initial_halfmove_clock: 0,
+            initial_last_move: None,
+            move_tree: MoveTree::new(),
+            current_node: 0,
         }
     }
 
@@ -46,6 +73,11 @@ impl ChessGame {
     pub fn exchange_promotion_pawn(&mut self, new_type: PieceType) {
         if let Some(promotion_pawn) = self.promotion_pawn {
             self.replace_piece_type(promotion_pawn, new_type);
+            if let Some(last_move) = self.executed_moves.last_mut() {
+                last_move.promotion = Some(new_type);
+                let promotion_move = last_move.clone();
+                self.enter_move_tree(&promotion_move);
+            }
             self.promotion_pawn = None;
             self.chess_moves = self.calculate_check();
         }
@@ -74,25 +106,53 @@ impl ChessGame {
             return;
         }
 
-        let mut capture = false;
+        let mut captured_piece = None;
         let en_passant_target = self.en_passant_target();
-        if en_passant_target.is_some() && chosen_move.target == *en_passant_target.unwrap() {
-            let victim_move = self.executed_moves.last().unwrap();
+        if chosen_move.piece.piece_type == PieceType::Pawn
+            && en_passant_target.is_some()
+            && chosen_move.target == *en_passant_target.unwrap()
+        {
+            let victim_move = self.last_move().unwrap();
+            captured_piece = self.piece_at(victim_move.target_square.position()).copied();
             EnPassantMove::new(&chosen_move.piece, victim_move, en_passant_target.unwrap())
                 .execute(self);
-            capture = true;
         } else if let Some(target_piece) = self.piece_at(chosen_move.target.position()) {
+            captured_piece = Some(*target_piece);
             CapturingMove::new(chosen_move.piece.clone(), target_piece.clone()).execute(self);
-            capture = true;
         } else if let Some(rook) = self.castling_rook(chosen_move) {
             CastlingMove::new(chosen_move.piece, *rook).execute(self)
         } else {
             Move::new(chosen_move.piece.clone(), chosen_move.target).execute(self);
         }
-        let executed_move = ExecutedMove::new_from(chosen_move, capture, self.is_check());
-        self.executed_moves.push(executed_move);
+        let executed_move = ExecutedMove::new_from(chosen_move, captured_piece, self.is_check());
+        self.executed_moves.push(executed_move.clone());
         self.chess_moves = self.calculate_check();
         self.promotion_pawn = self.check_promotion_pawn().cloned();
+        if self.promotion_pawn.is_none() {
+            self.enter_move_tree(&executed_move);
+        }
+    }
+
+    /** Continue the current node of the move tree with a given executed move. A new variation is
+    created if the current node doesn't have this move as a child yet. Promotion moves are entered
+    only after the promotion piece has been chosen. */
+    fn enter_move_tree(&mut self, executed_move: &ExecutedMove) {
+        self.current_node = self.move_tree.child_for(self.current_node, executed_move);
+    }
+
+    /** All the moves of this game including variations. */
+    pub fn move_tree(&self) -> &MoveTree {
+        &self.move_tree
+    }
+
+    /** The annotation of a given node of the move tree, for modification. */
+    pub fn annotation_mut(&mut self, node: NodeId) -> &mut Annotation {
+        self.move_tree.annotation_mut(node)
+    }
+
+    /** The node of the move tree which represents the current position. */
+    pub fn current_node(&self) -> NodeId {
+        self.current_node
     }
 
     /** All the moves of this game in the order they were executed. */
@@ -102,9 +162,19 @@ impl ChessGame {
 
     /** Whether a given piece has already moved in this game. */
     pub fn has_already_moved(&self, piece: &Piece) -> bool {
-        self.executed_moves()
-            .iter()
-            .any(|executed_move| executed_move.start_square == piece.start_square())
+        !piece.is_at_start()
+            || self
+                .executed_moves
+                .iter()
+                .any(|executed_move| executed_move.start_square == piece.start_square())
+    }
+
+    /** The last move executed in this game. For a game without executed moves, this is the move
+    which led to the initial position, if known. */
+    fn last_move(&self) -> Option<&ExecutedMove> {
+        self.executed_moves
+            .last()
+            .or(self.initial_last_move.as_ref())
     }
 
     /** The piece color which is allowed to move next. */
@@ -167,8 +237,7 @@ impl ChessGame {
     }
     /** En passant target for a given executed move. */
     fn en_passant_target(&self) -> Option<&BoardSquare> {
-        let all_moves = self.executed_moves();
-        let last_move = all_moves.last();
+        let last_move = self.last_move();
         if last_move.is_none() {
             return None;
         }
@@ -294,8 +363,9 @@ pub struct ExecutedMove {
     pub piece: Piece,
     pub start_square: BoardSquare,
     pub target_square: BoardSquare,
-    pub is_capture: bool,
+    pub captured_piece: Option<Piece>,
     pub is_chess: bool,
+    pub promotion: Option<PieceType>,
 }
 
 impl ExecutedMove {
@@ -303,26 +373,38 @@ impl ExecutedMove {
         piece: Piece,
         start_square: BoardSquare,
         target_square: BoardSquare,
-        is_capture: bool,
+        captured_piece: Option<Piece>,
         is_chess: bool,
     ) -> Self {
         Self {
             piece,
             start_square,
             target_square,
-            is_capture,
+            captured_piece,
             is_chess,
+            promotion: None,
         }
     }
-    fn new_from(source_move: &Move, is_capture: bool, is_chess: bool) -> Self {
+    fn new_from(source_move: &Move, captured_piece: Option<Piece>, is_chess: bool) -> Self {
         Self::new(
             source_move.piece,
             source_move.piece.square,
             source_move.target,
-            is_capture,
+            captured_piece,
             is_chess,
         )
     }
+    /** Whether this and another executed move move the same piece between the same squares and
+    promote to the same piece type. */
+    pub fn is_same_move(&self, other: &ExecutedMove) -> bool {
+        self.start_square == other.start_square
+            && self.target_square == other.target_square
+            && self.promotion == other.promotion
+    }
+    /** Whether this executed move captured a piece. */
+    pub fn is_capture(&self) -> bool {
+        self.captured_piece.is_some()
+    }
     /** Whether this executed move represents a castling. */
     pub fn is_castling(&self) -> bool {
         is_castling_move(&self.piece, &self.start_square, &self.target_square)
@@ -348,7 +430,7 @@ impl ExecutedMove {
             "{}{}{}{}{}{}",
             start.file().to_uppercase(),
             start.rank(),
-            if self.is_capture { 'x' } else { '-' },
+            if self.is_capture() { 'x' } else { '-' },
             target.file().to_uppercase(),
             target.rank(),
             match self.is_chess {
@@ -0,0 +1,183 @@
+use crate::domain::game::*;
+
+impl ChessGame {
+    /** The number of the current full move. It starts at 1, or at the number of a position set up
+    from FEN, and is incremented after each move of Black. */
+    pub fn fullmove_number(&self) -> usize {
+        (self.executed_moves.len() + self.initial_ply_offset()) / 2 + self.initial_fullmove_number
+    }
+    /** 1 if Black moves first in this game, 0 otherwise. Adding this offset to the number of moves
+    (plies) results in an even number whenever White is to move. */
+    pub fn initial_ply_offset(&self) -> usize {
+        match self.initial_color {
+            PieceColor::White => 0,
+            PieceColor::Black => 1,
+        }
+    }
+    /** The number of the full move of the initial position of this game. */
+    pub fn initial_fullmove_number(&self) -> usize {
+        self.initial_fullmove_number
+    }
+
+    /** The Standard Algebraic Notation (SAN) of a given possible move of this game, e.g. "Nbd7",
+    "exd5", "O-O" or "e8=Q+". A promotion move requires the new piece type. */
+    pub fn san(&self, chess_move: &Move, promotion: Option<PieceType>) -> String {
+        let mut result = self.san_without_check(chess_move, promotion);
+        let mut game_after = self.calculate_move(chess_move);
+        if let Some(new_type) = promotion {
+            game_after.exchange_promotion_pawn(new_type);
+        }
+        let opponent = chess_move.piece.color.opponent();
+        if game_after.is_check_color(opponent) {
+            result.push(match game_after.can_move(&opponent) {
+                true => '+',
+                false => '#',
+            });
+        }
+        result
+    }
+
+    /** The Standard Algebraic Notation of the moves of a given line of nodes of the move tree. The
+    line has to start at the root of the move tree, e.g. the path to a node. */
+    pub fn san_line(&self, line: &[NodeId]) -> Vec<String> {
+        let mut position = self.initial_position();
+        let mut result = Vec::new();
+        line.iter().for_each(|node| {
+            if let Some(executed_move) = &self.move_tree.node(*node).executed_move {
+                let chess_move = Move::new(executed_move.piece, executed_move.target_square);
+                result.push(position.san(&chess_move, executed_move.promotion));
+                position = position.calculate_move(&chess_move);
+                if let Some(new_type) = executed_move.promotion {
+                    position.exchange_promotion_pawn(new_type);
+                }
+            }
+        });
+        result
+    }
+
+    /** The Standard Algebraic Notation of a given possible move without check and mate markers. */
+    fn san_without_check(&self, chess_move: &Move, promotion: Option<PieceType>) -> String {
+        let piece = chess_move.piece;
+        let target = chess_move.target;
+        if is_castling_move(&piece, &piece.square, &target) {
+            return match target.x() > piece.square.x() {
+                true => "O-O",
+                false => "O-O-O",
+            }
+            .to_string();
+        }
+        let is_pawn = piece.piece_type == PieceType::Pawn;
+        let is_capture = self.piece_at(target.position()).is_some()
+            || (is_pawn && piece.square.x() != target.x());
+        let mut result = String::new();
+        if !is_pawn {
+            result.push(piece.piece_type.letter());
+            result.push_str(&self.san_disambiguation(chess_move));
+        } else if is_capture {
+            result.push_str(&piece.square.file());
+        }
+        if is_capture {
+            result.push('x');
+        }
+        result.push_str(&target.to_string());
+        if let Some(new_type) = promotion {
+            result.push('=');
+            result.push(new_type.letter());
+        }
+        result
+    }
+
+    /** The file, rank or square of a given move's piece required to distinguish it from other
+    pieces of the same type which are able to move to the same target. */
+    fn san_disambiguation(&self, chess_move: &Move) -> String {
+        let piece = chess_move.piece;
+        let rivals: Vec<&Piece> = self
+            .pieces
+            .iter()
+            .filter(|other| other.color == piece.color && other.piece_type == piece.piece_type)
+            .filter(|other| other.square != piece.square)
+            .filter(|other| {
+                self.possible_moves(other)
+                    .iter()
+                    .any(|other_move| other_move.target == chess_move.target)
+            })
+            .collect();
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals
+            .iter()
+            .all(|rival| rival.square.file() != piece.square.file())
+        {
+            piece.square.file()
+        } else if rivals
+            .iter()
+            .all(|rival| rival.square.rank() != piece.square.rank())
+        {
+            piece.square.rank()
+        } else {
+            piece.square.to_string()
+        }
+    }
+
+    /** Whether a given move is a pawn move onto the last rank, which requires a promotion. */
+    pub fn is_promotion_move(&self, chess_move: &Move) -> bool {
+        chess_move.piece.piece_type == PieceType::Pawn
+            && ["1".to_string(), "8".to_string()].contains(&chess_move.target.rank())
+    }
+
+    /** All possible moves of the color to move next together with their promotion piece type. A
+    promotion move is contained once for each piece type the pawn may be promoted to. */
+    pub fn possible_move_choices(&self) -> Vec<(Move, Option<PieceType>)> {
+        let mut result = Vec::new();
+        self.all_possible_moves(&self.next_move_color())
+            .into_iter()
+            .for_each(
+                |possible_move| match self.is_promotion_move(&possible_move) {
+                    true => PieceType::promotion_types()
+                        .into_iter()
+                        .for_each(|new_type| result.push((possible_move.clone(), Some(new_type)))),
+                    false => result.push((possible_move, None)),
+                },
+            );
+        result
+    }
+
+    /** The possible move of the color to move next which is denoted by a given text together with
+    its promotion piece type. The text may be in Standard Algebraic Notation, e.g. "Nf3", or in
+    coordinate notation, e.g. "g1f3", "G1-F3" or "e7e8q". None if no such move is possible. */
+    pub fn parse_move(&self, text: &str) -> Option<(Move, Option<PieceType>)> {
+        let san = normalize_san(text);
+        let coordinates = normalize_coordinates(text);
+        self.possible_move_choices()
+            .into_iter()
+            .find(|(candidate, promotion)| {
+                self.san_without_check(candidate, *promotion)
+                    .replace('=', "")
+                    == san
+                    || coordinate_notation(candidate, *promotion) == coordinates
+            })
+    }
+}
+
+/** The coordinate notation of a given move as used by chess engines, e.g. "g1f3" or "e7e8q". */
+pub fn coordinate_notation(chess_move: &Move, promotion: Option<PieceType>) -> String {
+    let mut result = format!("{}{}", chess_move.piece.square, chess_move.target);
+    if let Some(new_type) = promotion {
+        result.push(new_type.letter().to_ascii_lowercase());
+    }
+    result
+}
+
+fn normalize_san(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(['+', '#', '!', '?'])
+        .replace('=', "")
+        .replace('0', "O")
+}
+
+fn normalize_coordinates(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(['+', '#', '!', '?'])
+        .to_lowercase()
+        .replace(['-', 'x', '='], "")
+}
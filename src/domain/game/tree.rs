@@ -0,0 +1,167 @@
+use crate::domain::game::annotation::Annotation;
+use crate::domain::game::ExecutedMove;
+
+/** Identifier of a single node within a [MoveTree]. */
+pub type NodeId = usize;
+
+/** A single node of a [MoveTree]. Every node but the root holds the move leading to it. */
+#[derive(Clone, Debug)]
+pub struct MoveNode {
+    pub executed_move: Option<ExecutedMove>,
+    pub annotation: Annotation,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/** All the moves of a game including its variations. The first child of a node continues the main
+line of that node, all further children are side variations. */
+#[derive(Clone, Debug)]
+pub struct MoveTree {
+    nodes: Vec<MoveNode>,
+}
+
+impl Default for MoveTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MoveTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![MoveNode {
+                executed_move: None,
+                annotation: Annotation::default(),
+                parent: None,
+                children: vec![],
+            }],
+        }
+    }
+
+    /** The node which represents the start position, i.e. before any move. */
+    pub fn root(&self) -> NodeId {
+        0
+    }
+    pub fn node(&self, id: NodeId) -> &MoveNode {
+        &self.nodes[id]
+    }
+    /** The annotation of the position of a given node, for modification. */
+    pub fn annotation_mut(&mut self, id: NodeId) -> &mut Annotation {
+        &mut self.nodes[id].annotation
+    }
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+    /** The children of a given node: The main line continuation first, then the variations. */
+    pub fn children(&self, id: NodeId) -> Vec<NodeId> {
+        self.nodes[id].children.clone()
+    }
+    /** The siblings of a given node including the node itself, in the order of their parent. */
+    pub fn siblings(&self, id: NodeId) -> Vec<NodeId> {
+        match self.parent(id) {
+            Some(parent) => self.children(parent),
+            None => vec![id],
+        }
+    }
+
+    /** The child of a given node which represents a given move. The child is created as a new
+    variation if there is none yet. */
+    pub fn child_for(&mut self, parent: NodeId, executed_move: &ExecutedMove) -> NodeId {
+        let existing = self.nodes[parent].children.iter().find(|child| {
+            self.nodes[**child]
+                .executed_move
+                .as_ref()
+                .is_some_and(|child_move| child_move.is_same_move(executed_move))
+        });
+        if let Some(child) = existing {
+            return *child;
+        }
+        let child = self.nodes.len();
+        self.nodes.push(MoveNode {
+            executed_move: Some(executed_move.clone()),
+            annotation: Annotation::default(),
+            parent: Some(parent),
+            children: vec![],
+        });
+        self.nodes[parent].children.push(child);
+        child
+    }
+
+    /** All nodes from the first move up to and including a given node. Empty for the root. */
+    pub fn path(&self, id: NodeId) -> Vec<NodeId> {
+        let mut result = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            result.push(current);
+            current = parent;
+        }
+        result.reverse();
+        result
+    }
+    /** All nodes following a given node along the main line of that node. */
+    pub fn continuation(&self, id: NodeId) -> Vec<NodeId> {
+        let mut result = Vec::new();
+        let mut current = id;
+        while let Some(first_child) = self.nodes[current].children.first() {
+            result.push(*first_child);
+            current = *first_child;
+        }
+        result
+    }
+    /** The last node of the main line, i.e. the end of the game. */
+    pub fn mainline_end(&self) -> NodeId {
+        *self
+            .continuation(self.root())
+            .last()
+            .unwrap_or(&self.root())
+    }
+
+    /** Move the variation containing a given node one position up, i.e. closer to the main line.
+    A variation at the first position becomes the main line. */
+    pub fn promote_variation(&mut self, id: NodeId) {
+        if let Some((parent, index)) = self.variation_start(id) {
+            if index > 0 {
+                self.nodes[parent].children.swap(index, index - 1);
+            }
+        }
+    }
+    /** Move the variation containing a given node one position down, i.e. away from the main line. */
+    pub fn demote_variation(&mut self, id: NodeId) {
+        if let Some((parent, index)) = self.variation_start(id) {
+            if index + 1 < self.nodes[parent].children.len() {
+                self.nodes[parent].children.swap(index, index + 1);
+            }
+        }
+    }
+    /** Delete the innermost variation containing a given node including all of its moves, i.e.
+    from the first node towards the root which is not the first child of its parent. Nothing is
+    deleted if the node is part of the main line. The node from which the deleted moves started is
+    returned, the given node if nothing is deleted. */
+    pub fn delete_variation(&mut self, id: NodeId) -> NodeId {
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            let children = &mut self.nodes[parent].children;
+            if children.first() != Some(&current) {
+                children.retain(|child| *child != current);
+                return parent;
+            }
+            current = parent;
+        }
+        id
+    }
+
+    /** The parent and child index of the first node from a given node towards the root that has
+    siblings. None if there is no such node. */
+    fn variation_start(&self, id: NodeId) -> Option<(NodeId, usize)> {
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            let children = &self.nodes[parent].children;
+            if children.len() > 1 {
+                let index = children.iter().position(|child| *child == current)?;
+                return Some((parent, index));
+            }
+            current = parent;
+        }
+        None
+    }
+}
@@ -1,3 +1,9 @@
+pub mod book;
 pub mod chessboard;
+pub mod clock;
+pub mod eco;
+pub mod evaluation;
 pub mod game;
+pub mod pgn;
 pub mod pieces;
+pub mod search;
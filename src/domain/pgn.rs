@@ -0,0 +1,412 @@
+use crate::domain::eco::classify;
+use crate::domain::game::annotation::nag_of_symbol;
+use crate::domain::game::fen::START_FEN;
+use crate::domain::game::tree::{MoveTree, NodeId};
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceColor;
+
+const MAX_LINE_LENGTH: usize = 80;
+
+/** A single token of the Portable Game Notation (PGN). */
+#[derive(Clone, Debug, PartialEq)]
+enum PgnToken {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    VariationStart,
+    VariationEnd,
+    Move(String),
+    Result(String),
+}
+
+/** The Portable Game Notation (PGN) of a given game including all of its variations. */
+pub fn export_pgn(game: &ChessGame) -> String {
+    export_pgn_with_tags(game, &[])
+}
+
+/** The Portable Game Notation (PGN) of a given game with given tags, which replace the tags of the
+same name, e.g. the players, or are added after them. A given result also ends the moves, e.g. for
+a game adjudicated as a draw. The opening of the main line is added as ECO and Opening tags if it is
+recognized. */
+pub fn export_pgn_with_tags(game: &ChessGame, tags: &[(&str, String)]) -> String {
+    let mut all_tags: Vec<(&str, String)> = [
+        ("Event", "?"),
+        ("Site", "?"),
+        ("Date", "????.??.??"),
+        ("Round", "?"),
+        ("White", "?"),
+        ("Black", "?"),
+    ]
+    .iter()
+    .map(|(name, value)| (*name, value.to_string()))
+    .collect();
+    all_tags.push(("Result", game_result(game)));
+    let mut mainline = game.clone();
+    mainline.go_to_node(game.move_tree().mainline_end());
+    if let Some(opening) = classify(&mainline) {
+        all_tags.push(("ECO", opening.code.to_string()));
+        all_tags.push(("Opening", opening.name.to_string()));
+    }
+    for (name, value) in tags {
+        match all_tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some(tag) => tag.1 = value.clone(),
+            None => all_tags.push((name, value.clone())),
+        }
+    }
+    let result = all_tags
+        .iter()
+        .find(|(name, _)| *name == "Result")
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default();
+    let mut text = String::new();
+    all_tags.iter().for_each(|(name, value)| {
+        text.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)))
+    });
+    let initial_fen = game.initial_position().fen();
+    if initial_fen != START_FEN {
+        text.push_str(&format!("[SetUp \"1\"]\n[FEN \"{}\"]\n", initial_fen));
+    }
+    text.push('\n');
+
+    let tree = game.move_tree();
+    let mut tokens = Vec::new();
+    write_annotation(tree, tree.root(), &mut tokens);
+    write_children(
+        tree,
+        &game.initial_position(),
+        tree.root(),
+        true,
+        &mut tokens,
+    );
+    tokens.push(result);
+    text.push_str(&wrap_tokens(tokens));
+    text.push('\n');
+    text
+}
+
+/** The position where a variation starts together with what is needed to continue the line it is
+an alternative to after the variation ends. */
+struct OpenVariation {
+    node: NodeId,
+    position: ChessGame,
+    position_before_move: ChessGame,
+}
+
+/** Create a game from the first game of a given Portable Game Notation (PGN) text. The main line of
+the game is executed, all variations are available in its move tree. */
+pub fn import_pgn(text: &str) -> Result<ChessGame, String> {
+    let mut game = ChessGame::new();
+    // The positions are saved instead of replaying the moves from the start at each variation.
+    let mut position_before_move: Option<ChessGame> = None;
+    let mut variations: Vec<OpenVariation> = Vec::new();
+    for token in tokenize(text)? {
+        match token {
+            PgnToken::VariationStart => {
+                let current = game.current_node();
+                let parent = game.move_tree().parent(current);
+                let (Some(parent), Some(before_move)) = (parent, position_before_move.take())
+                else {
+                    return Err("Variation without a preceding move".to_string());
+                };
+                variations.push(OpenVariation {
+                    node: current,
+                    position: game.save_position(),
+                    position_before_move: before_move.clone(),
+                });
+                game.restore_position(parent, before_move);
+            }
+            PgnToken::VariationEnd => {
+                let variation = variations.pop().ok_or("Unbalanced variation end")?;
+                game.restore_position(variation.node, variation.position);
+                position_before_move = Some(variation.position_before_move);
+            }
+            PgnToken::Move(text) => {
+                let (chess_move, promotion) = game
+                    .parse_move(&text)
+                    .ok_or(format!("Illegal move: {}", text))?;
+                position_before_move = Some(game.save_position());
+                game.execute_move(&chess_move);
+                if let Some(new_type) = promotion {
+                    game.exchange_promotion_pawn(new_type);
+                }
+            }
+            PgnToken::Comment(comment) => {
+                let current = game.current_node();
+                game.annotation_mut(current).add_pgn_comment(&comment);
+            }
+            PgnToken::Nag(nag) => {
+                let current = game.current_node();
+                game.annotation_mut(current).nags.push(nag);
+            }
+            PgnToken::Result(_) => break,
+            PgnToken::Tag(name, value) if name == "FEN" => {
+                game = ChessGame::from_fen(&value)?;
+                position_before_move = None;
+            }
+            PgnToken::Tag(..) => {}
+        }
+    }
+    if !variations.is_empty() {
+        return Err("Unbalanced variation start".to_string());
+    }
+    let mainline_end = game.move_tree().mainline_end();
+    game.go_to_node(mainline_end);
+    Ok(game)
+}
+
+/** The PGN result of the main line of a given game: "1-0", "0-1", "1/2-1/2" or "*". */
+fn game_result(game: &ChessGame) -> String {
+    let mut end = game.clone();
+    end.go_to_node(game.move_tree().mainline_end());
+    if end.is_check_mate() {
+        return match end.next_move_color() {
+            PieceColor::White => "0-1",
+            PieceColor::Black => "1-0",
+        }
+        .to_string();
+    }
+    match end.is_stalemate() {
+        true => "1/2-1/2",
+        false => "*",
+    }
+    .to_string()
+}
+
+/** Write the moves following a given node: The main line move first, then its variations in
+parentheses, and finally the continuation of the main line. */
+fn write_children(
+    tree: &MoveTree,
+    position: &ChessGame,
+    parent: NodeId,
+    force_number: bool,
+    tokens: &mut Vec<String>,
+) {
+    let children = tree.children(parent);
+    let Some(main_child) = children.first() else {
+        return;
+    };
+    let main_position = write_move(tree, position, *main_child, force_number, tokens);
+    children.iter().skip(1).for_each(|variation| {
+        tokens.push("(".to_string());
+        let variation_position = write_move(tree, position, *variation, true, tokens);
+        write_children(tree, &variation_position, *variation, false, tokens);
+        tokens.push(")".to_string());
+    });
+    write_children(
+        tree,
+        &main_position,
+        *main_child,
+        children.len() > 1,
+        tokens,
+    );
+}
+
+/** Write the move of a given node, preceded by its move number if required. Returns the position
+after the move. */
+fn write_move(
+    tree: &MoveTree,
+    position: &ChessGame,
+    node: NodeId,
+    force_number: bool,
+    tokens: &mut Vec<String>,
+) -> ChessGame {
+    let executed_move = tree
+        .node(node)
+        .executed_move
+        .as_ref()
+        .expect("Only the root node has no move");
+    let chess_move = Move::new(executed_move.piece, executed_move.target_square);
+    let move_number = position.fullmove_number();
+    match position.next_move_color() {
+        PieceColor::White => tokens.push(format!("{}.", move_number)),
+        PieceColor::Black if force_number => tokens.push(format!("{}...", move_number)),
+        PieceColor::Black => {}
+    }
+    tokens.push(position.san(&chess_move, executed_move.promotion));
+    write_annotation(tree, node, tokens);
+    let mut result = position.calculate_move(&chess_move);
+    if let Some(new_type) = executed_move.promotion {
+        result.exchange_promotion_pawn(new_type);
+    }
+    result
+}
+
+/** Write the glyphs and the comment of a given node. */
+fn write_annotation(tree: &MoveTree, node: NodeId, tokens: &mut Vec<String>) {
+    let annotation = &tree.node(node).annotation;
+    annotation
+        .nags
+        .iter()
+        .for_each(|nag| tokens.push(format!("${}", nag)));
+    if let Some(comment) = annotation.pgn_comment() {
+        tokens.push(format!("{{{}}}", comment));
+    }
+}
+
+/** Join given tokens with spaces into lines of limited length. No space is put after an opening
+or before a closing parenthesis. */
+fn wrap_tokens(tokens: Vec<String>) -> String {
+    let mut result = String::new();
+    let mut line = String::new();
+    let mut previous = String::new();
+    tokens.into_iter().for_each(|token| {
+        let separator = match previous == "(" || token == ")" || line.is_empty() {
+            true => "",
+            false => " ",
+        };
+        if line.len() + separator.len() + token.len() > MAX_LINE_LENGTH && separator == " " {
+            result.push_str(&line);
+            result.push('\n');
+            line.clear();
+        } else {
+            line.push_str(separator);
+        }
+        line.push_str(&token);
+        previous = token;
+    });
+    result.push_str(&line);
+    result
+}
+
+/** Split a given PGN text into its tokens. */
+fn tokenize(text: &str) -> Result<Vec<PgnToken>, String> {
+    let mut result = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => result.push(parse_tag(&mut chars)?),
+            '{' => {
+                let comment: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                result.push(PgnToken::Comment(comment.trim().to_string()));
+            }
+            ';' => {
+                chars.by_ref().take_while(|c| *c != '\n').for_each(drop);
+            }
+            '(' => result.push(PgnToken::VariationStart),
+            ')' => result.push(PgnToken::VariationEnd),
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut word = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || "[]{}();".contains(*next) {
+                        break;
+                    }
+                    word.push(*next);
+                    chars.next();
+                }
+                result.append(&mut parse_word(&word)?);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/** Parse a tag pair after its opening bracket up to its closing bracket, e.g. `Event "Casual Game"]`.
+The value is a string in quotes, in which a backslash escapes a quote or a backslash. */
+fn parse_tag(chars: &mut impl Iterator<Item = char>) -> Result<PgnToken, String> {
+    let name: String = chars.by_ref().take_while(|c| *c != '"').collect();
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) || name.contains(']') {
+        return Err(format!("Invalid tag: {}", name));
+    }
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => break,
+            Some('\\') => value.extend(chars.next()),
+            Some(c) => value.push(c),
+            None => return Err(format!("Unterminated value of tag {}", name)),
+        }
+    }
+    match chars.find(|c| !c.is_whitespace()) {
+        Some(']') => Ok(PgnToken::Tag(name.to_string(), value)),
+        _ => Err(format!("Missing ']' after tag {}", name)),
+    }
+}
+
+/** A given tag value with quotes and backslashes escaped by a backslash. */
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/** Parse a single word of the move text. Move numbers result in no token, moves with a suffix
+like "!?" result in a move and a glyph token. */
+fn parse_word(word: &str) -> Result<Vec<PgnToken>, String> {
+    if ["1-0", "0-1", "1/2-1/2", "*"].contains(&word) {
+        return Ok(vec![PgnToken::Result(word.to_string())]);
+    }
+    if let Some(number) = word.strip_prefix('$') {
+        let nag = number
+            .parse()
+            .map_err(|_| format!("Invalid annotation glyph: {}", word))?;
+        return Ok(vec![PgnToken::Nag(nag)]);
+    }
+    if let Some(nag) = nag_of_symbol(word) {
+        return Ok(vec![PgnToken::Nag(nag)]);
+    }
+    let move_text = match word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.') {
+        true => word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.'),
+        false => word,
+    };
+    let san = move_text.trim_end_matches(['!', '?']);
+    let mut result = Vec::new();
+    if !san.is_empty() {
+        result.push(PgnToken::Move(san.to_string()));
+    }
+    if let Some(nag) = nag_of_symbol(&move_text[san.len()..]) {
+        result.push(PgnToken::Nag(nag));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNOTATED_PGN: &str = r#"[Event "Test"]
+[SetUp "1"]
+[FEN "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"]
+
+3. Bb5 $1 {The Ruy Lopez} (3. Bc4 Nf6 (3... Bc5 4. c3 $5) 4. d3) 3... a6 $6
+4. Ba4 {[%clk 0:04:58] Keeps the pin [%eval 0.31]} Nf6 (4... b5 5. Bb3) 5. O-O *
+"#;
+
+    #[test]
+    fn export_and_import_keep_moves_variations_and_annotations() {
+        let game = import_pgn(ANNOTATED_PGN).unwrap();
+        let exported = export_pgn(&game);
+        let imported = import_pgn(&exported).unwrap();
+        assert_eq!(export_pgn(&imported), exported);
+        assert_eq!(imported.fen(), game.fen());
+        assert_eq!(
+            imported.initial_position().fen(),
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"
+        );
+        assert!(exported.contains("[SetUp \"1\"]"));
+        assert!(
+            exported.contains("3. Bb5 $1 {The Ruy Lopez} (3. Bc4 Nf6 (3... Bc5 4. c3 $5) 4. d3)")
+        );
+        assert!(exported.contains("(4... b5 5. Bb3)"));
+        assert!(exported.contains("{[%clk 0:04:58][%eval 0.31] Keeps the pin}"));
+        let tree = imported.move_tree();
+        assert_eq!(tree.children(tree.root()).len(), 2);
+        assert_eq!(tree.path(tree.mainline_end()).len(), 5);
+    }
+
+    #[test]
+    fn tag_values_are_escaped_and_may_contain_brackets() {
+        let value = r#"A "quoted" [name] with \ backslash"#.to_string();
+        let exported = export_pgn_with_tags(&ChessGame::new(), &[("Event", value.clone())]);
+        assert!(exported.contains(r#"[Event "A \"quoted\" [name] with \\ backslash"]"#));
+        let tokens = tokenize(&exported).unwrap();
+        assert!(tokens.contains(&PgnToken::Tag("Event".to_string(), value)));
+        assert!(import_pgn(&exported).is_ok());
+    }
+
+    #[test]
+    fn unbalanced_variations_are_rejected() {
+        assert!(import_pgn("1. e4 (1. d4 *").is_err());
+        assert!(import_pgn("1. e4 1... e5) *").is_err());
+        assert!(import_pgn("(1. e4) *").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use crate::domain::chessboard::BoardSquare;
 
 #[derive(Clone, Debug, PartialEq, Copy)]
@@ -10,6 +12,60 @@ pub enum PieceType {
     Pawn,
 }
 
+impl PieceType {
+    /** The upper case letter of this piece type used in algebraic notation, e.g. 'N' for a Knight. */
+    pub fn letter(&self) -> char {
+        match self {
+            PieceType::King => 'K',
+            PieceType::Queen => 'Q',
+            PieceType::Rook => 'R',
+            PieceType::Bishop => 'B',
+            PieceType::Knight => 'N',
+            PieceType::Pawn => 'P',
+        }
+    }
+    /** The piece type of a given upper case letter used in algebraic notation, e.g. 'N'. */
+    pub fn from_letter(letter: char) -> Option<PieceType> {
+        match letter {
+            'K' => Some(PieceType::King),
+            'Q' => Some(PieceType::Queen),
+            'R' => Some(PieceType::Rook),
+            'B' => Some(PieceType::Bishop),
+            'N' => Some(PieceType::Knight),
+            'P' => Some(PieceType::Pawn),
+            _ => None,
+        }
+    }
+    /** The Unicode chess symbol of this piece type in a given color, e.g. '♘' for a white and '♞'
+    for a black Knight. */
+    pub fn glyph(&self, color: PieceColor) -> char {
+        let glyphs = match color {
+            PieceColor::White => ['♔', '♕', '♖', '♗', '♘', '♙'],
+            PieceColor::Black => ['♚', '♛', '♜', '♝', '♞', '♟'],
+        };
+        glyphs[*self as usize]
+    }
+    /** The common material value of this piece type in pawns. The King has no material value. */
+    pub fn value(&self) -> i32 {
+        match self {
+            PieceType::King => 0,
+            PieceType::Queen => 9,
+            PieceType::Rook => 5,
+            PieceType::Bishop | PieceType::Knight => 3,
+            PieceType::Pawn => 1,
+        }
+    }
+    /** All piece types a pawn may be promoted to. */
+    pub fn promotion_types() -> Vec<PieceType> {
+        vec![
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ]
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum PieceColor {
     White,
@@ -44,6 +100,20 @@ impl Piece {
             start_square: position,
         }
     }
+    /** This piece, but having started the game on a given square. */
+    pub(crate) fn with_start_square(self, start_square: BoardSquare) -> Self {
+        Self {
+            start_square,
+            ..self
+        }
+    }
+    /** The letter of this piece as used in FEN: Upper case for White, lower case for Black. */
+    pub fn letter(&self) -> char {
+        match self.color {
+            PieceColor::White => self.piece_type.letter(),
+            PieceColor::Black => self.piece_type.letter().to_ascii_lowercase(),
+        }
+    }
     /** The square a given piece started the game. */
     pub fn start_square(&self) -> BoardSquare {
         self.start_square
@@ -54,6 +124,14 @@ impl Piece {
     }
 }
 
+impl Display for Piece {
+    /** The letter of this piece followed by its square, e.g. "Nf3" for a white and "nf6" for a
+    black Knight. */
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.letter(), self.square)
+    }
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Hash)]
 pub struct PieceDelta {
     pub delta: (i8, i8),
@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::domain::evaluation::Evaluation;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceType;
+
+/** The score of a position in which the side to move is checkmated. */
+const MATE_SCORE: i32 = 1_000_000;
+
+/** The best move of the color to move next in a given game together with its promotion piece type,
+found by searching a given number of moves (plies) ahead. None if there is no possible move. */
+pub fn best_move(game: &ChessGame, depth: u32) -> Option<(Move, Option<PieceType>)> {
+    best_move_unless_cancelled(game, depth, &AtomicBool::new(false))
+}
+
+/** The best move like [best_move], unless a given flag is set meanwhile by another thread, which
+stops the search soon. The result of a cancelled search is meaningless. */
+pub fn best_move_unless_cancelled(
+    game: &ChessGame,
+    depth: u32,
+    cancelled: &AtomicBool,
+) -> Option<(Move, Option<PieceType>)> {
+    let mut best = None;
+    let mut alpha = -MATE_SCORE - 1;
+    for (chess_move, promotion) in ordered_move_choices(game) {
+        let position = position_after(game, &chess_move, promotion);
+        let score = -negamax(
+            &position,
+            depth.max(1) - 1,
+            1,
+            -MATE_SCORE - 1,
+            -alpha,
+            cancelled,
+        );
+        if best.is_none() || score > alpha {
+            alpha = score;
+            best = Some((chess_move, promotion));
+        }
+    }
+    best
+}
+
+/** The score of a given position from the point of view of the color to move next, searching a
+given number of moves (plies) ahead within the window of alpha and beta. Once a given flag is set,
+the search returns right away. */
+fn negamax(
+    game: &ChessGame,
+    depth: u32,
+    ply: i32,
+    mut alpha: i32,
+    beta: i32,
+    cancelled: &AtomicBool,
+) -> i32 {
+    if cancelled.load(Ordering::Relaxed) {
+        return 0;
+    }
+    let color = game.next_move_color();
+    if depth == 0 {
+        return Evaluation::of(game).total_for(color);
+    }
+    let choices = ordered_move_choices(game);
+    if choices.is_empty() {
+        // Earlier mates score better than later ones.
+        return match game.is_check_color(color) {
+            true => -MATE_SCORE + ply,
+            false => 0,
+        };
+    }
+    for (chess_move, promotion) in choices {
+        let position = position_after(game, &chess_move, promotion);
+        let score = -negamax(&position, depth - 1, ply + 1, -beta, -alpha, cancelled);
+        if score >= beta {
+            return beta;
+        }
+        alpha = alpha.max(score);
+    }
+    alpha
+}
+
+/** All possible moves of the color to move next in a given game, the most valuable captures and
+promotions first. Good moves first let the search skip more of the other moves. */
+fn ordered_move_choices(game: &ChessGame) -> Vec<(Move, Option<PieceType>)> {
+    let mut choices = game.possible_move_choices();
+    choices.sort_by_key(|(chess_move, promotion)| {
+        let captured = game
+            .piece_at(chess_move.target.position())
+            .map_or(0, |piece| piece.piece_type.value());
+        let promoted = promotion.map_or(0, |new_type| new_type.value());
+        -(captured + promoted)
+    });
+    choices
+}
+
+/** The position of a given game after a given move including its promotion. */
+fn position_after(game: &ChessGame, chess_move: &Move, promotion: Option<PieceType>) -> ChessGame {
+    let mut result = game.calculate_move(chess_move);
+    if let Some(new_type) = promotion {
+        result.exchange_promotion_pawn(new_type);
+    }
+    result
+}
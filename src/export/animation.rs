@@ -0,0 +1,57 @@
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame};
+
+use crate::domain::game::ChessGame;
+use crate::export::raster::{render_diagram, PieceSprites};
+use crate::export::DiagramOptions;
+
+/** The default time each position of an animation is shown in milliseconds. */
+pub const DEFAULT_FRAME_DELAY_MS: u32 = 1000;
+/** The longest time each position of an animation may be shown in milliseconds. */
+pub const MAX_FRAME_DELAY_MS: u32 = 60_000;
+/** The final position of an animation is shown this many times longer than the others. */
+const FINAL_FRAME_FACTOR: u32 = 3;
+/** The speed of the color quantization of the GIF encoder from 1 (best quality) to 30 (fastest). */
+const ENCODER_SPEED: i32 = 10;
+
+/** An animated Graphics Interchange Format (GIF) image of all executed moves of a given game: The
+initial position followed by one frame per move, each highlighting that move. The animation is
+repeated endlessly. The last move of the options is ignored, all other options apply to every
+frame. */
+pub fn export_gif(
+    game: &ChessGame,
+    options: &DiagramOptions,
+    sprites: &PieceSprites,
+    frame_delay_ms: u32,
+) -> Result<Vec<u8>, String> {
+    let mut result = Vec::new();
+    {
+        let mut encoder = GifEncoder::new_with_speed(&mut result, ENCODER_SPEED);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|error| error.to_string())?;
+        let moves = game.executed_moves();
+        let mut position = game.initial_position();
+        for index in 0..=moves.len() {
+            let last_move = index.checked_sub(1).map(|ply| &moves[ply]);
+            if let Some(executed_move) = last_move {
+                position.replay_move(executed_move);
+            }
+            let frame_options = DiagramOptions {
+                last_move: last_move
+                    .map(|executed_move| (executed_move.start_square, executed_move.target_square)),
+                ..options.clone()
+            };
+            let delay_ms = match index == moves.len() {
+                true => frame_delay_ms.saturating_mul(FINAL_FRAME_FACTOR),
+                false => frame_delay_ms,
+            };
+            let image = render_diagram(&position, &frame_options, sprites);
+            let frame = Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1));
+            encoder
+                .encode_frame(frame)
+                .map_err(|error| error.to_string())?;
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,187 @@
+use crate::domain::chessboard::BoardSquare;
+use crate::domain::game::annotation::{Arrow, MarkerColor, SquareMarker};
+use crate::domain::game::ChessGame;
+use crate::domain::pieces::PieceColor;
+
+pub mod animation;
+pub mod raster;
+pub mod svg;
+
+/** The default length of the edge of an exported board in pixels. */
+pub const DEFAULT_BOARD_SIZE: u32 = 480;
+/** The smallest length of the edge of an exported board in pixels at which the pieces and coordinates
+are still recognizable. */
+pub const MIN_BOARD_SIZE: u32 = 64;
+
+/** The colors of an exported board diagram. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiagramColors {
+    pub light_square: (u8, u8, u8),
+    pub dark_square: (u8, u8, u8),
+    pub white_pieces: (u8, u8, u8),
+    pub black_pieces: (u8, u8, u8),
+    pub last_move: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+    pub coordinates: (u8, u8, u8),
+}
+
+impl Default for DiagramColors {
+    /** The brown colors of a wooden board, which also print well in gray. */
+    fn default() -> Self {
+        Self {
+            light_square: (240, 217, 181),
+            dark_square: (181, 136, 99),
+            white_pieces: (255, 255, 255),
+            black_pieces: (0, 0, 0),
+            last_move: (205, 210, 106),
+            background: (255, 255, 255),
+            coordinates: (0, 0, 0),
+        }
+    }
+}
+
+/** The appearance of an exported board diagram. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagramOptions {
+    /** The length of the edge of the board in pixels, without the coordinates. */
+    pub board_size: u32,
+    /** Black at the bottom of the board instead of White. */
+    pub flipped: bool,
+    /** Rank numbers left of and file letters below the board. */
+    pub coordinates: bool,
+    /** The start and target square of the last move, which are highlighted. */
+    pub last_move: Option<(BoardSquare, BoardSquare)>,
+    pub squares: Vec<SquareMarker>,
+    pub arrows: Vec<Arrow>,
+    pub colors: DiagramColors,
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        Self {
+            board_size: DEFAULT_BOARD_SIZE,
+            flipped: false,
+            coordinates: true,
+            last_move: None,
+            squares: Vec::new(),
+            arrows: Vec::new(),
+            colors: DiagramColors::default(),
+        }
+    }
+}
+
+impl DiagramOptions {
+    /** The options showing the last move of a given game seen from a given color. */
+    pub fn for_game(game: &ChessGame, bottom_color: PieceColor) -> Self {
+        Self {
+            flipped: bottom_color == PieceColor::Black,
+            last_move: game
+                .executed_moves()
+                .last()
+                .map(|executed_move| (executed_move.start_square, executed_move.target_square)),
+            ..Self::default()
+        }
+    }
+
+    /** The length of the edge of a single square in pixels. */
+    pub fn square_size(&self) -> u32 {
+        (self.board_size / 8).max(1)
+    }
+    /** The width of the margin left of and below the board where the coordinates are shown. */
+    pub fn margin(&self) -> u32 {
+        match self.coordinates {
+            true => (self.square_size() / 3).max(6),
+            false => 0,
+        }
+    }
+    /** The width of the whole diagram in pixels. */
+    pub fn width(&self) -> u32 {
+        self.margin() + 8 * self.square_size()
+    }
+    /** The height of the whole diagram in pixels. */
+    pub fn height(&self) -> u32 {
+        8 * self.square_size() + self.margin()
+    }
+
+    /** The pixel position of the upper left corner of a given square. */
+    pub fn square_origin(&self, square: BoardSquare) -> (u32, u32) {
+        let (x, y) = self.view_position(square);
+        let size = self.square_size();
+        (self.margin() + x * size, y * size)
+    }
+    /** The pixel position of the center of a given square. */
+    pub fn square_center(&self, square: BoardSquare) -> (f32, f32) {
+        let (x, y) = self.square_origin(square);
+        let half = self.square_size() as f32 / 2.0;
+        (x as f32 + half, y as f32 + half)
+    }
+
+    /** The corners of the outline of an arrow between the centers of two given squares: The shaft
+    followed by the head. */
+    pub fn arrow_outline(&self, from: BoardSquare, to: BoardSquare) -> Vec<(f32, f32)> {
+        let (x1, y1) = self.square_center(from);
+        let (x2, y2) = self.square_center(to);
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(1.0);
+        // Unit vectors along and across the arrow.
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        let (nx, ny) = (-dy, dx);
+        let size = self.square_size() as f32;
+        let shaft = size * 0.08;
+        let head_width = size * 0.25;
+        let head_length = (size * 0.4).min(length);
+        let (bx, by) = (x2 - dx * head_length, y2 - dy * head_length);
+        vec![
+            (x1 + nx * shaft, y1 + ny * shaft),
+            (bx + nx * shaft, by + ny * shaft),
+            (bx + nx * head_width, by + ny * head_width),
+            (x2, y2),
+            (bx - nx * head_width, by - ny * head_width),
+            (bx - nx * shaft, by - ny * shaft),
+            (x1 - nx * shaft, y1 - ny * shaft),
+        ]
+    }
+
+    /** The column and row of a given square as seen in the diagram, (0, 0) being the upper left. */
+    fn view_position(&self, square: BoardSquare) -> (u32, u32) {
+        let (x, y) = square.position();
+        match self.flipped {
+            false => (x as u32, y as u32),
+            true => (7 - x as u32, 7 - y as u32),
+        }
+    }
+
+    /** The squares in the order of the columns and rows of the diagram. */
+    pub fn view_squares(&self) -> Vec<BoardSquare> {
+        let ranks = "87654321".chars();
+        let mut result: Vec<BoardSquare> = ranks
+            .flat_map(|rank| ('a'..='h').map(move |file| BoardSquare::new(rank, file)))
+            .collect();
+        if self.flipped {
+            result.reverse();
+        }
+        result
+    }
+    /** The file letters from left to right and the rank numbers from top to bottom. */
+    pub fn coordinate_labels(&self) -> (Vec<char>, Vec<char>) {
+        let mut files: Vec<char> = ('a'..='h').collect();
+        let mut ranks: Vec<char> = ('1'..='8').rev().collect();
+        if self.flipped {
+            files.reverse();
+            ranks.reverse();
+        }
+        (files, ranks)
+    }
+}
+
+/** The color of arrows and highlighted squares of a given marker color. */
+pub fn marker_color(marker_color: MarkerColor) -> (u8, u8, u8) {
+    match marker_color {
+        MarkerColor::Green => (21, 120, 27),
+        MarkerColor::Red => (200, 30, 30),
+        MarkerColor::Yellow => (230, 180, 0),
+        MarkerColor::Blue => (30, 90, 200),
+    }
+}
+
+/** The opacity of highlighted squares and arrows drawn above the board. */
+pub const MARKER_OPACITY: f32 = 0.6;
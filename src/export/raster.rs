@@ -0,0 +1,261 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+use image::{ImageOutputFormat, Rgba, RgbaImage};
+
+use crate::domain::chessboard::SquareColor;
+use crate::domain::game::ChessGame;
+use crate::domain::pieces::{PieceColor, PieceType};
+use crate::export::{marker_color, DiagramOptions, MARKER_OPACITY};
+
+/** The file of the sprite sheet of the pieces within the resource directory. */
+pub const PIECES_FILE: &str = "chess_pieces.png";
+/** The length of the edge of a single piece within the sprite sheet in pixels. */
+pub const PIECES_TILE_SIZE: u32 = 64;
+
+/** The rows of the 3x5 pixel glyphs of the coordinates, the leftmost pixel being the highest bit. */
+const COORDINATE_GLYPHS: [(char, [u8; 5]); 16] = [
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b110, 0b001, 0b010, 0b100, 0b111]),
+    ('3', [0b110, 0b001, 0b010, 0b001, 0b110]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b110, 0b001, 0b110]),
+    ('6', [0b011, 0b100, 0b110, 0b101, 0b010]),
+    ('7', [0b111, 0b001, 0b010, 0b010, 0b010]),
+    ('8', [0b010, 0b101, 0b010, 0b101, 0b010]),
+    ('a', [0b000, 0b011, 0b101, 0b101, 0b011]),
+    ('b', [0b100, 0b110, 0b101, 0b101, 0b110]),
+    ('c', [0b000, 0b011, 0b100, 0b100, 0b011]),
+    ('d', [0b001, 0b011, 0b101, 0b101, 0b011]),
+    ('e', [0b000, 0b010, 0b111, 0b100, 0b011]),
+    ('f', [0b011, 0b100, 0b110, 0b100, 0b100]),
+    ('g', [0b011, 0b101, 0b011, 0b001, 0b110]),
+    ('h', [0b100, 0b110, 0b101, 0b101, 0b101]),
+];
+
+/** The images of the pieces, cut out of a sprite sheet. As in the GUI, a piece is drawn by
+multiplying its image with the color of the piece. */
+#[derive(Debug, Clone)]
+pub struct PieceSprites {
+    /** The images in the order of [PieceType]. */
+    tiles: Vec<RgbaImage>,
+}
+
+impl PieceSprites {
+    /** The sprites of a sprite sheet file with tiles of a given size, the pieces being the first
+    six tiles of the first row. */
+    pub fn load(path: &Path, tile_size: u32) -> Result<Self, String> {
+        let sheet = image::open(path)
+            .map_err(|error| format!("Cannot load {}: {}", path.display(), error))?
+            .to_rgba8();
+        if sheet.width() < 6 * tile_size || sheet.height() < tile_size {
+            return Err(format!("{} is too small for six pieces", path.display()));
+        }
+        let tiles = (0..6)
+            .map(|index| imageops::crop_imm(&sheet, index * tile_size, 0, tile_size, tile_size))
+            .map(|tile| tile.to_image())
+            .collect();
+        Ok(Self { tiles })
+    }
+    /** The sprites of the sprite sheet within a given resource directory. */
+    pub fn load_default(resource_dir: &str) -> Result<Self, String> {
+        Self::load(&Path::new(resource_dir).join(PIECES_FILE), PIECES_TILE_SIZE)
+    }
+
+    /** The sprites scaled to a given size. */
+    fn scaled(&self, size: u32) -> Vec<RgbaImage> {
+        self.tiles
+            .iter()
+            .map(|tile| imageops::resize(tile, size, size, FilterType::Triangle))
+            .collect()
+    }
+}
+
+/** An image of the current position of a given game with pieces of given sprites. */
+pub fn render_diagram(
+    game: &ChessGame,
+    options: &DiagramOptions,
+    sprites: &PieceSprites,
+) -> RgbaImage {
+    let colors = &options.colors;
+    let size = options.square_size();
+    let mut image =
+        RgbaImage::from_pixel(options.width(), options.height(), rgba(colors.background));
+    for square in options.view_squares() {
+        let (x, y) = options.square_origin(square);
+        let color = match square.color() {
+            SquareColor::White => colors.light_square,
+            SquareColor::Black => colors.dark_square,
+        };
+        fill_rect(&mut image, (x, y), size, color, 1.0);
+        if options
+            .last_move
+            .is_some_and(|(from, to)| from == square || to == square)
+        {
+            fill_rect(&mut image, (x, y), size, colors.last_move, MARKER_OPACITY);
+        }
+    }
+    for marker in &options.squares {
+        let origin = options.square_origin(marker.square);
+        fill_rect(
+            &mut image,
+            origin,
+            size,
+            marker_color(marker.color),
+            MARKER_OPACITY,
+        );
+    }
+    let tiles = sprites.scaled(size);
+    for piece in &game.pieces {
+        let tile = &tiles[piece_index(piece.piece_type)];
+        let color = match piece.color {
+            PieceColor::White => colors.white_pieces,
+            PieceColor::Black => colors.black_pieces,
+        };
+        draw_sprite(&mut image, tile, options.square_origin(piece.square), color);
+    }
+    for arrow in &options.arrows {
+        let outline = options.arrow_outline(arrow.from, arrow.to);
+        fill_polygon(
+            &mut image,
+            &outline,
+            marker_color(arrow.color),
+            MARKER_OPACITY,
+        );
+    }
+    if options.coordinates {
+        let (files, ranks) = options.coordinate_labels();
+        let margin = options.margin();
+        let scale = (margin / 7).max(1);
+        let (glyph_width, glyph_height) = (3 * scale, 5 * scale);
+        for (index, file) in files.iter().enumerate() {
+            let x = margin + index as u32 * size + size.saturating_sub(glyph_width) / 2;
+            let y = 8 * size + margin.saturating_sub(glyph_height) / 2;
+            draw_glyph(&mut image, *file, (x, y), scale, colors.coordinates);
+        }
+        for (index, rank) in ranks.iter().enumerate() {
+            let x = margin.saturating_sub(glyph_width) / 2;
+            let y = index as u32 * size + size.saturating_sub(glyph_height) / 2;
+            draw_glyph(&mut image, *rank, (x, y), scale, colors.coordinates);
+        }
+    }
+    image
+}
+
+/** A Portable Network Graphics (PNG) diagram of the current position of a given game. */
+pub fn export_png(
+    game: &ChessGame,
+    options: &DiagramOptions,
+    sprites: &PieceSprites,
+) -> Result<Vec<u8>, String> {
+    let mut result = Cursor::new(Vec::new());
+    render_diagram(game, options, sprites)
+        .write_to(&mut result, ImageOutputFormat::Png)
+        .map_err(|error| error.to_string())?;
+    Ok(result.into_inner())
+}
+
+/** The index of the sprite of a given piece type, the same as its offset in the GUI's font. */
+fn piece_index(piece_type: PieceType) -> usize {
+    piece_type as usize
+}
+
+fn rgba(color: (u8, u8, u8)) -> Rgba<u8> {
+    Rgba([color.0, color.1, color.2, 255])
+}
+
+/** Blend a given color with a given opacity into the pixel at a given position. */
+fn blend(image: &mut RgbaImage, (x, y): (u32, u32), color: (u8, u8, u8), opacity: f32) {
+    if x >= image.width() || y >= image.height() {
+        return;
+    }
+    let pixel = image.get_pixel_mut(x, y);
+    let mix =
+        |old: u8, new: u8| (old as f32 * (1.0 - opacity) + new as f32 * opacity).round() as u8;
+    pixel.0 = [
+        mix(pixel.0[0], color.0),
+        mix(pixel.0[1], color.1),
+        mix(pixel.0[2], color.2),
+        255,
+    ];
+}
+
+fn fill_rect(
+    image: &mut RgbaImage,
+    (x, y): (u32, u32),
+    size: u32,
+    color: (u8, u8, u8),
+    opacity: f32,
+) {
+    for dy in 0..size {
+        for dx in 0..size {
+            blend(image, (x + dx, y + dy), color, opacity);
+        }
+    }
+}
+
+/** Draw a given sprite multiplied with a given color at a given position. */
+fn draw_sprite(image: &mut RgbaImage, sprite: &RgbaImage, (x, y): (u32, u32), color: (u8, u8, u8)) {
+    for (dx, dy, pixel) in sprite.enumerate_pixels() {
+        let [r, g, b, a] = pixel.0;
+        let tint = |channel: u8, color: u8| (channel as u16 * color as u16 / 255) as u8;
+        let tinted = (tint(r, color.0), tint(g, color.1), tint(b, color.2));
+        blend(image, (x + dx, y + dy), tinted, a as f32 / 255.0);
+    }
+}
+
+/** Fill a given polygon, smoothing its edges by sampling four points of each pixel. */
+fn fill_polygon(image: &mut RgbaImage, polygon: &[(f32, f32)], color: (u8, u8, u8), opacity: f32) {
+    let min_x = polygon
+        .iter()
+        .map(|p| p.0)
+        .fold(f32::MAX, f32::min)
+        .max(0.0) as u32;
+    let min_y = polygon
+        .iter()
+        .map(|p| p.1)
+        .fold(f32::MAX, f32::min)
+        .max(0.0) as u32;
+    let max_x = polygon.iter().map(|p| p.0).fold(0.0, f32::max).ceil() as u32;
+    let max_y = polygon.iter().map(|p| p.1).fold(0.0, f32::max).ceil() as u32;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let samples = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)];
+            let inside = samples
+                .iter()
+                .filter(|(sx, sy)| contains(polygon, (x as f32 + sx, y as f32 + sy)))
+                .count();
+            if inside > 0 {
+                blend(image, (x, y), color, opacity * inside as f32 / 4.0);
+            }
+        }
+    }
+}
+
+/** Whether a given point is inside a given polygon, using the even-odd rule. */
+fn contains(polygon: &[(f32, f32)], (x, y): (f32, f32)) -> bool {
+    let mut result = false;
+    for (index, (x1, y1)) in polygon.iter().enumerate() {
+        let (x2, y2) = polygon[(index + 1) % polygon.len()];
+        if (*y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            result = !result;
+        }
+    }
+    result
+}
+
+/** Draw the coordinate glyph of a given character scaled by a given factor. */
+fn draw_glyph(image: &mut RgbaImage, c: char, (x, y): (u32, u32), scale: u32, color: (u8, u8, u8)) {
+    let Some((_, rows)) = COORDINATE_GLYPHS.iter().find(|(glyph, _)| *glyph == c) else {
+        return;
+    };
+    for (row, bits) in rows.iter().enumerate() {
+        for column in 0..3 {
+            if bits & (0b100 >> column) != 0 {
+                let origin = (x + column * scale, y + row as u32 * scale);
+                fill_rect(image, origin, scale, color, 1.0);
+            }
+        }
+    }
+}
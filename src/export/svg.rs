@@ -0,0 +1,130 @@
+use crate::domain::chessboard::{BoardSquare, SquareColor};
+use crate::domain::game::ChessGame;
+use crate::domain::pieces::PieceColor;
+use crate::export::{marker_color, DiagramOptions, MARKER_OPACITY};
+
+/** A Scalable Vector Graphics (SVG) diagram of the current position of a given game. The pieces are
+drawn with the Unicode chess symbols of the fonts of the viewer. */
+pub fn export_svg(game: &ChessGame, options: &DiagramOptions) -> String {
+    let colors = &options.colors;
+    let size = options.square_size();
+    let mut elements = vec![format!(
+        r#"<rect width="{}" height="{}" fill="{}"/>"#,
+        options.width(),
+        options.height(),
+        hex(colors.background)
+    )];
+    for square in options.view_squares() {
+        let (x, y) = options.square_origin(square);
+        let fill = match square.color() {
+            SquareColor::White => colors.light_square,
+            SquareColor::Black => colors.dark_square,
+        };
+        elements.push(square_rect(x, y, size, fill, 1.0));
+        if options
+            .last_move
+            .is_some_and(|(from, to)| from == square || to == square)
+        {
+            elements.push(square_rect(x, y, size, colors.last_move, MARKER_OPACITY));
+        }
+    }
+    for marker in &options.squares {
+        let (x, y) = options.square_origin(marker.square);
+        let color = marker_color(marker.color);
+        elements.push(square_rect(x, y, size, color, MARKER_OPACITY));
+    }
+    for piece in &game.pieces {
+        let (x, y) = options.square_center(piece.square);
+        // The solid symbols are filled with the color of the piece and outlined.
+        let (fill, stroke) = match piece.color {
+            PieceColor::White => (colors.white_pieces, colors.black_pieces),
+            PieceColor::Black => (colors.black_pieces, colors.black_pieces),
+        };
+        elements.push(format!(
+            r#"<text x="{:.1}" y="{:.1}" font-size="{:.1}" text-anchor="middle" dominant-baseline="central" fill="{}" stroke="{}" stroke-width="{:.1}">{}</text>"#,
+            x,
+            y,
+            size as f32 * 0.8,
+            hex(fill),
+            hex(stroke),
+            size as f32 / 40.0,
+            piece.piece_type.glyph(PieceColor::Black)
+        ));
+    }
+    for arrow in &options.arrows {
+        elements.push(arrow_polygon(
+            options,
+            arrow.from,
+            arrow.to,
+            marker_color(arrow.color),
+        ));
+    }
+    if options.coordinates {
+        let (files, ranks) = options.coordinate_labels();
+        let margin = options.margin() as f32;
+        let font_size = margin * 0.8;
+        let label = |x: f32, y: f32, text: char| {
+            format!(
+                r#"<text x="{:.1}" y="{:.1}" font-family="sans-serif" font-size="{:.1}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+                x,
+                y,
+                font_size,
+                hex(colors.coordinates),
+                text
+            )
+        };
+        for (index, file) in files.iter().enumerate() {
+            let x = margin + (index as f32 + 0.5) * size as f32;
+            elements.push(label(x, 8.0 * size as f32 + margin / 2.0, *file));
+        }
+        for (index, rank) in ranks.iter().enumerate() {
+            let y = (index as f32 + 0.5) * size as f32;
+            elements.push(label(margin / 2.0, y, *rank));
+        }
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n{}\n</svg>\n",
+        options.width(),
+        options.height(),
+        options.width(),
+        options.height(),
+        elements.join("\n")
+    )
+}
+
+fn square_rect(x: u32, y: u32, size: u32, fill: (u8, u8, u8), opacity: f32) -> String {
+    format!(
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="{}"/>"#,
+        x,
+        y,
+        size,
+        size,
+        hex(fill),
+        opacity
+    )
+}
+
+/** An arrow from the center of a given square to the center of another one. */
+fn arrow_polygon(
+    options: &DiagramOptions,
+    from: BoardSquare,
+    to: BoardSquare,
+    color: (u8, u8, u8),
+) -> String {
+    let points: Vec<String> = options
+        .arrow_outline(from, to)
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect();
+    format!(
+        r#"<polygon points="{}" fill="{}" fill-opacity="{}"/>"#,
+        points.join(" "),
+        hex(color),
+        MARKER_OPACITY
+    )
+}
+
+/** The hexadecimal notation of a given color, e.g. "#F0D9B5". */
+fn hex(color: (u8, u8, u8)) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.0, color.1, color.2)
+}
@@ -0,0 +1,8 @@
+/** The rules of chess, the configuration, the export of diagrams and network play shared by the
+graphical and the terminal frontend, the server of games for other tools and engine tournaments. */
+pub mod config;
+pub mod domain;
+pub mod export;
+pub mod network;
+pub mod server;
+pub mod tournament;
@@ -1,42 +1,1093 @@
-use crate::domain::game::ChessGame;
+use std::path::Path;
+use std::rc::Rc;
+use std::{env, fs, mem, process, thread};
+
+use bracket_lib::prelude::VirtualKeyCode;
+use chess::{config, domain, export, network};
+
+use crate::config::{PlayerType, Settings, USAGE};
+use crate::domain::book::OpeningBook;
+use crate::domain::chessboard::BoardSquare;
+use crate::domain::clock::ChessClock;
+use crate::domain::game::annotation::{Arrow, MarkerColor, SquareMarker};
+use crate::domain::game::tree::NodeId;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pgn::{export_pgn, import_pgn};
 use crate::domain::pieces::{PieceColor, PieceType};
+use crate::export::animation::{export_gif, DEFAULT_FRAME_DELAY_MS};
+use crate::export::raster::{export_png, PieceSprites};
+use crate::export::svg::export_svg;
+use crate::export::DiagramOptions;
+use crate::network::protocol::Message;
+use crate::network::session::{NetworkEvent, NetworkSession};
+use crate::network::NetworkRole;
+use crate::ui::analysis::Analysis;
+use crate::ui::board_view::{BoardView, Perspective};
+use crate::ui::command_line::CommandLine;
+use crate::ui::computer_player::ComputerPlayer;
+use crate::ui::menu::{
+    cycle_mode, cycle_search_depth, cycle_time_control, human_color, swap_colors, GameEnd, Menu,
+    MenuItem, MenuScreen,
+};
+use crate::ui::move_list::MoveList;
+use crate::ui::replay::Replay;
+use crate::ui::status::StatusMessage;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::Themes;
 use crate::ui::user_move::UserMove;
 
-mod domain;
 mod ui;
 
+const DIAGRAM_SVG_FILE: &str = "diagram.svg";
+const DIAGRAM_PNG_FILE: &str = "diagram.png";
+const ANIMATION_FILE: &str = "game.gif";
+
 /** Main entry point of the application. */
 fn main() {
-    ui::main(MainState::new()).unwrap()
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    match Settings::load(&args).and_then(MainState::new) {
+        Ok(main_state) => ui::main(main_state).unwrap(),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    }
 }
 
 /** All possible states of the application. */
 #[derive(Debug)]
 enum AppState {
     AwaitingPieceSelection,
-    AwaitingMoveSelection { user_move: UserMove },
+    AwaitingMoveSelection {
+        user_move: UserMove,
+    },
+    DraggingPiece {
+        user_move: UserMove,
+    },
+    EditingComment {
+        node: NodeId,
+        input: TextInput,
+    },
+    EnteringMove {
+        command_line: CommandLine,
+    },
+    InMenu {
+        menu: Menu,
+    },
+    SettingUpPosition {
+        input: TextInput,
+        message: Option<String>,
+    },
 }
 
+/** The current node, the replayed ply and the number of executed moves a displayed position is
+built for. */
+type DisplayedKey = (NodeId, Option<usize>, usize);
+
 /** Main state consisting of the chess game and the state of the application. */
 struct MainState {
     game: ChessGame,
     app_state: AppState,
+    replay: Replay,
+    /** The position displayed last, which is only built again when its key changes. */
+    displayed: Option<(DisplayedKey, Rc<ChessGame>)>,
+    marker_start: Option<BoardSquare>,
+    board_cursor: Option<(i8, i8)>,
+    perspective: Perspective,
+    move_list: MoveList,
+    themes: Themes,
+    settings: Settings,
+    clock: Option<ChessClock>,
+    computer: ComputerPlayer,
+    /** The evaluation of the displayed position and the hint of the best move in it. */
+    analysis: Analysis,
+    /** Whether the terms of the evaluation are shown instead of the move list. */
+    shows_evaluation: bool,
+    /** The opening book whose moves the computer plays, if any. */
+    book: Option<OpeningBook>,
+    game_end: Option<GameEnd>,
+    position_fen: Option<String>,
+    quit: bool,
+    network: Option<NetworkSession>,
+    /** What happened last in a network game, e.g. a chat message of the opponent. */
+    network_message: Option<String>,
+    /** The result of the last action of the user, e.g. saving the game. */
+    status: StatusMessage,
 }
 
 impl MainState {
-    fn new() -> Self {
-        Self {
-            game: ChessGame::new(),
-            app_state: AppState::AwaitingPieceSelection,
+    /** The initial state of the application with given settings. */
+    fn new(mut settings: Settings) -> Result<Self, String> {
+        let mut game = match &settings.fen {
+            Some(fen) => ChessGame::from_fen(fen)?,
+            None => ChessGame::new(),
+        };
+        let mut network_message = None;
+        let network = match settings.network.clone() {
+            Some(NetworkRole::Host(port)) => {
+                let local_color = settings
+                    .remote_color()
+                    .unwrap_or(PieceColor::Black)
+                    .opponent();
+                network_message = Some(format!("Waiting for the opponent on port {}", port));
+                Some(NetworkSession::host(port, local_color)?)
+            }
+            Some(NetworkRole::Guest(address)) => {
+                let (session, host_game) = NetworkSession::connect(&address)?;
+                if let Some(local_color) = session.local_color() {
+                    settings.set_remote_color(local_color.opponent());
+                }
+                game = host_game;
+                Some(session)
+            }
+            Some(NetworkRole::Spectator(address)) => {
+                let (session, host_game) = NetworkSession::watch(&address)?;
+                game = host_game;
+                Some(session)
+            }
+            None => None,
+        };
+        // A network game starts right away instead of showing the menu.
+        let app_state = match network {
+            Some(_) => AppState::AwaitingPieceSelection,
+            None => AppState::InMenu {
+                menu: Menu::new(MenuScreen::Main),
+            },
+        };
+        let book = settings
+            .book
+            .as_deref()
+            .map(OpeningBook::load)
+            .transpose()?;
+        let mut status = StatusMessage::new();
+        let mut themes = Themes::load(&settings.resource_dir).unwrap_or_else(|error| {
+            status.show(format!("Cannot load themes: {}", error));
+            Themes::built_in()
+        });
+        if let Some(theme) = &settings.theme {
+            if !themes.select(theme) {
+                return Err(format!("Unknown theme '{}'", theme));
+            }
+        }
+        Ok(Self {
+            game,
+            app_state,
+            replay: Replay::new(),
+            displayed: None,
+            marker_start: None,
+            board_cursor: None,
+            perspective: Perspective::HumanSide,
+            move_list: MoveList::new(),
+            themes,
+            clock: settings.time_control.map(ChessClock::new),
+            computer: ComputerPlayer::new(settings.search_depth),
+            analysis: Analysis::new(settings.search_depth),
+            shows_evaluation: false,
+            book,
+            game_end: None,
+            position_fen: settings.fen.clone(),
+            quit: false,
+            network,
+            network_message,
+            status,
+            settings,
+        })
+    }
+
+    /** Replace the game by a given one which starts with the clock and the computer player of the
+    current settings. */
+    fn reset_game(&mut self, game: ChessGame) {
+        self.game = game;
+        self.replay = Replay::new();
+        self.displayed = None;
+        self.move_list.clear();
+        self.marker_start = None;
+        self.board_cursor = None;
+        self.clock = self.settings.time_control.map(ChessClock::new);
+        self.computer = ComputerPlayer::new(self.settings.search_depth);
+        self.game_end = None;
+    }
+
+    /** Start a new game with the players, the time control and the start position of the current
+    settings. */
+    fn start_new_game(&mut self) {
+        let game = match &self.settings.fen {
+            Some(fen) => ChessGame::from_fen(fen),
+            None => Ok(ChessGame::new()),
+        };
+        match game {
+            Ok(game) => {
+                self.reset_game(game);
+                self.perspective = Perspective::HumanSide;
+                self.app_state = AppState::AwaitingPieceSelection;
+            }
+            Err(error) => self.show_menu_message(error),
+        }
+    }
+
+    /** Whether the game is paused because a menu or another screen is shown instead of it. */
+    fn is_paused(&self) -> bool {
+        matches!(
+            self.app_state,
+            AppState::InMenu { .. } | AppState::SettingUpPosition { .. }
+        )
+    }
+
+    /** Whether the game is over by checkmate, stalemate, agreement of the players or because a player
+    ran out of time. */
+    fn is_game_over(&self) -> bool {
+        self.game_end.is_some()
+            || self.game.is_check_mate()
+            || self.game.is_stalemate()
+            || self
+                .clock
+                .as_ref()
+                .is_some_and(|clock| clock.flagged().is_some())
+    }
+
+    /** The color which has to act next: The color of a pawn waiting for its promotion piece, otherwise
+    the color to move next. */
+    fn acting_color(&self) -> PieceColor {
+        self.game
+            .promotion_pawn()
+            .map_or(self.game.next_move_color(), |pawn| pawn.color)
+    }
+
+    /** Whether the user may make the next move of the game or choose the piece of their promotion. */
+    fn is_human_turn(&self) -> bool {
+        self.settings.player(self.acting_color()) == PlayerType::Human && !self.is_game_over()
+    }
+
+    /** Let a given number of milliseconds pass on the clock, and let the computer move if it is its
+    turn. Nothing happens while the game is paused. */
+    fn update_players(&mut self, elapsed_ms: f32) {
+        self.status.advance(elapsed_ms);
+        self.update_network();
+        if self.is_paused() {
+            return;
+        }
+        let is_game_over = self.is_game_over();
+        let color = self.game.next_move_color();
+        let move_count = self.game.executed_moves().len();
+        if let Some(clock) = &mut self.clock {
+            if !is_game_over {
+                clock.advance(elapsed_ms, color, move_count);
+            }
+        }
+        if self.settings.player(self.acting_color()) != PlayerType::Computer || self.is_game_over()
+        {
+            return;
+        }
+        let book_move = self
+            .book
+            .as_ref()
+            .and_then(|book| book.choose_move(&self.game));
+        if let Some((chess_move, promotion)) = book_move {
+            self.execute_move_choice(&chess_move, promotion);
+            self.discard_prepared_move();
+            return;
+        }
+        match self.computer.found_move(&self.game) {
+            Some((chess_move, promotion)) => {
+                self.execute_move_choice(&chess_move, promotion);
+                self.discard_prepared_move();
+            }
+            None => self.computer.start(&self.game),
+        }
+    }
+
+    /** Handle everything the opponent of a network game has done since the last call. The opponent
+    may also move while the game is paused. */
+    fn update_network(&mut self) {
+        let Some(network) = &mut self.network else {
+            return;
+        };
+        let is_spectator = network.is_spectator();
+        for event in network.poll(&self.game) {
+            let message = match event {
+                NetworkEvent::Connected if is_spectator => "Host connected".to_string(),
+                NetworkEvent::Disconnected if is_spectator => "Host disconnected".to_string(),
+                NetworkEvent::Connected => "Opponent connected".to_string(),
+                NetworkEvent::Disconnected => "Opponent disconnected".to_string(),
+                NetworkEvent::Move(chess_move, promotion) => {
+                    self.execute_move_choice(&chess_move, promotion);
+                    self.discard_prepared_move();
+                    continue;
+                }
+                NetworkEvent::DrawOffered if self.is_game_over() => continue,
+                NetworkEvent::DrawOffered => {
+                    self.app_state = AppState::InMenu {
+                        menu: Menu::new(MenuScreen::DrawOffer),
+                    };
+                    "Opponent offers a draw".to_string()
+                }
+                NetworkEvent::DrawAccepted if is_spectator => {
+                    self.game_end = Some(GameEnd::DrawAgreement);
+                    continue;
+                }
+                NetworkEvent::DrawAccepted => {
+                    self.game_end = Some(GameEnd::DrawAgreement);
+                    "Opponent accepts the draw".to_string()
+                }
+                NetworkEvent::DrawDeclined => "Opponent declines the draw".to_string(),
+                NetworkEvent::Resigned(color) => {
+                    self.game_end = Some(GameEnd::Resignation(color));
+                    continue;
+                }
+                NetworkEvent::Chat(text) => format!("Opponent: {}", text),
+                NetworkEvent::Synced(game) => {
+                    self.reset_game(*game);
+                    self.discard_prepared_move();
+                    "Position synchronized".to_string()
+                }
+                NetworkEvent::SpectatorJoined => "Spectator joined".to_string(),
+                NetworkEvent::SpectatorLeft => "Spectator left".to_string(),
+                NetworkEvent::Error(error) => error,
+            };
+            self.network_message = Some(message);
+        }
+    }
+
+    /** Whether the game of a host is watched without playing. */
+    fn is_spectator(&self) -> bool {
+        self.network
+            .as_ref()
+            .is_some_and(|network| network.is_spectator())
+    }
+
+    /** Send a given message to the opponent, if this is a network game. */
+    fn send_to_network(&mut self, message: Message) {
+        if let Some(network) = &mut self.network {
+            network.send(message);
+        }
+    }
+
+    /** Discard the move the user has prepared for a position which has changed meanwhile. */
+    fn discard_prepared_move(&mut self) {
+        match &mut self.app_state {
+            AppState::AwaitingMoveSelection { .. } | AppState::DraggingPiece { .. } => {
+                self.app_state = AppState::AwaitingPieceSelection
+            }
+            AppState::EnteringMove { command_line } => {
+                let input = command_line.input.clone();
+                *command_line = CommandLine::new(&self.game);
+                command_line.input = input;
+            }
+            _ => {}
+        }
+    }
+
+    /** The position to be displayed: Either the one of the live game or a historical position of the
+    replay. It is only replayed again when the current node, the replayed ply or the number of
+    executed moves has changed. */
+    fn displayed_game(&mut self) -> Rc<ChessGame> {
+        let move_count = self.game.executed_moves().len();
+        let key = (self.game.current_node(), self.replay.ply(), move_count);
+        match &self.displayed {
+            Some((known, game)) if *known == key => Rc::clone(game),
+            _ => {
+                let ply = self.replay.ply().unwrap_or(move_count);
+                let game = Rc::new(self.game.position_after(ply));
+                self.displayed = Some((key, Rc::clone(&game)));
+                game
+            }
+        }
+    }
+
+    /** The color shown at the bottom of the board for a given displayed game. */
+    fn bottom_color(&self, game: &ChessGame) -> PieceColor {
+        self.perspective
+            .bottom_color(game, human_color(&self.settings))
+    }
+
+    /** The view of the board for a given displayed game. */
+    fn view(&self, game: &ChessGame) -> BoardView {
+        self.perspective.view(game, human_color(&self.settings))
+    }
+
+    /** The node of the move tree whose position is displayed. */
+    fn displayed_node(&self) -> NodeId {
+        let tree = self.game.move_tree();
+        match self.replay.ply() {
+            Some(0) => tree.root(),
+            Some(ply) => tree.path(self.game.current_node())[ply - 1],
+            None => self.game.current_node(),
+        }
+    }
+
+    /** Whether the user may change the move tree or move to another node of it. Not in network
+    games, where both players must stay in the same position, so their moves can only be replayed.
+    The user is told if it is not possible. */
+    fn may_edit_move_tree(&mut self) -> bool {
+        if self.network.is_some() {
+            self.network_message = Some("Cannot leave the line of a network game".to_string());
+            return false;
+        }
+        true
+    }
+
+    /** Make the displayed historical position the current position of the game. The following
+    moves remain in the move tree of the game. False if this is not possible since a network game is
+    replayed. */
+    fn take_over_displayed_position(&mut self) -> bool {
+        if let Some(ply) = self.replay.ply() {
+            if !self.may_edit_move_tree() {
+                return false;
+            }
+            self.game.go_to_ply(ply);
+            self.replay.last();
+        }
+        true
+    }
+
+    /** Make the position of a given node of the move tree the current position. */
+    fn go_to_node(&mut self, node: NodeId) {
+        if !self.may_edit_move_tree() {
+            return;
+        }
+        self.game.go_to_node(node);
+        self.replay.last();
+    }
+
+    /** Make a sibling of the current node the current position, e.g. the next variation. */
+    fn go_to_sibling(&mut self, offset: isize) {
+        if !self.may_edit_move_tree() {
+            return;
+        }
+        self.take_over_displayed_position();
+        let current = self.game.current_node();
+        let siblings = self.game.move_tree().siblings(current);
+        let index = siblings
+            .iter()
+            .position(|node| *node == current)
+            .unwrap_or(0);
+        if let Some(sibling) = index
+            .checked_add_signed(offset)
+            .and_then(|sibling_index| siblings.get(sibling_index))
+        {
+            self.go_to_node(*sibling);
+        }
+    }
+
+    fn evaluate_key(&mut self, key: VirtualKeyCode) {
+        match self.app_state {
+            AppState::EditingComment { .. } => return self.evaluate_comment_key(key),
+            AppState::EnteringMove { .. } => return self.evaluate_command_key(key),
+            AppState::InMenu { .. } => return self.evaluate_menu_key(key),
+            AppState::SettingUpPosition { .. } => return self.evaluate_setup_key(key),
+            _ => {}
+        }
+        if self.board_cursor.is_some() && self.evaluate_cursor_key(key) {
+            return;
+        }
+        let move_count = self.game.executed_moves().len();
+        let tree = self.game.move_tree();
+        let continuation = tree.continuation(self.game.current_node());
+        match key {
+            VirtualKeyCode::Home => self.replay.first(move_count),
+            VirtualKeyCode::Left => self.replay.previous(move_count),
+            VirtualKeyCode::Right if !self.replay.is_active() => {
+                if let Some(next_node) = continuation.first() {
+                    self.go_to_node(*next_node);
+                }
+            }
+            VirtualKeyCode::Right => self.replay.next(move_count),
+            VirtualKeyCode::End => match continuation.last() {
+                Some(last_node) => self.go_to_node(*last_node),
+                None => self.replay.last(),
+            },
+            VirtualKeyCode::Up => self.go_to_sibling(-1),
+            VirtualKeyCode::Down => self.go_to_sibling(1),
+            VirtualKeyCode::PageUp => {
+                if self.take_over_displayed_position() && self.may_edit_move_tree() {
+                    self.game.promote_variation();
+                }
+            }
+            VirtualKeyCode::PageDown => {
+                if self.take_over_displayed_position() && self.may_edit_move_tree() {
+                    self.game.demote_variation();
+                }
+            }
+            VirtualKeyCode::Delete => {
+                if self.take_over_displayed_position() && self.may_edit_move_tree() {
+                    self.game.delete_variation();
+                }
+            }
+            VirtualKeyCode::Space => self.replay.toggle_auto_play(move_count),
+            VirtualKeyCode::Plus | VirtualKeyCode::Equals | VirtualKeyCode::NumpadAdd => {
+                self.replay.faster()
+            }
+            VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => self.replay.slower(),
+            VirtualKeyCode::F2 => self.save_pgn(),
+            VirtualKeyCode::F3 if self.network.is_some() => {
+                self.network_message = Some("Cannot load a network game".to_string())
+            }
+            VirtualKeyCode::F3 => {
+                self.load_pgn();
+            }
+            VirtualKeyCode::F4 => {
+                let node = self.displayed_node();
+                let comment = &self.game.move_tree().node(node).annotation.comment;
+                self.app_state = AppState::EditingComment {
+                    node,
+                    input: TextInput::new(comment),
+                };
+                return;
+            }
+            VirtualKeyCode::F6 => self.export_diagram(),
+            VirtualKeyCode::F7 => self.export_animation(),
+            VirtualKeyCode::Tab => {
+                if !self.take_over_displayed_position() {
+                    return;
+                }
+                self.app_state = AppState::EnteringMove {
+                    command_line: CommandLine::new(&self.game),
+                };
+                return;
+            }
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                self.board_cursor = Some(self.initial_cursor());
+                return;
+            }
+            VirtualKeyCode::F => {
+                let game = self.displayed_game();
+                self.perspective = self.perspective.flipped(&game, human_color(&self.settings));
+                return;
+            }
+            VirtualKeyCode::A => {
+                let game = self.displayed_game();
+                let human_color = human_color(&self.settings);
+                self.perspective = self.perspective.toggled_auto(&game, human_color);
+                return;
+            }
+            VirtualKeyCode::N => self.move_list.toggle_notation(),
+            VirtualKeyCode::H => match self.analysis.toggle_hint() {
+                true => self.status.show("Hint: on"),
+                false => self.status.show("Hint: off"),
+            },
+            VirtualKeyCode::E => self.shows_evaluation = !self.shows_evaluation,
+            VirtualKeyCode::T => {
+                self.themes.select_next();
+                let name = &self.themes.selected().name;
+                self.status.show(format!("Theme: {}", name));
+            }
+            VirtualKeyCode::LBracket => self.move_list.page_up(),
+            VirtualKeyCode::RBracket => {
+                let slot_count = continuation.len() + move_count + self.game.initial_ply_offset();
+                self.move_list.page_down(slot_count)
+            }
+            VirtualKeyCode::Key1 => self.toggle_nag(1),
+            VirtualKeyCode::Key2 => self.toggle_nag(2),
+            VirtualKeyCode::Key3 => self.toggle_nag(3),
+            VirtualKeyCode::Key4 => self.toggle_nag(4),
+            VirtualKeyCode::Key5 => self.toggle_nag(5),
+            VirtualKeyCode::Key6 => self.toggle_nag(6),
+            VirtualKeyCode::Escape => {
+                self.app_state = AppState::InMenu {
+                    menu: Menu::new(MenuScreen::InGame),
+                };
+                return;
+            }
+            _ => return,
+        }
+        self.app_state = AppState::AwaitingPieceSelection;
+    }
+
+    fn evaluate_comment_key(&mut self, key: VirtualKeyCode) {
+        if let AppState::EditingComment { node, input } = &mut self.app_state {
+            match key {
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                    self.game.annotation_mut(*node).comment = input.text.trim().to_string();
+                }
+                VirtualKeyCode::Back => {
+                    input.backspace();
+                    return;
+                }
+                VirtualKeyCode::Escape => {}
+                _ => return,
+            }
+            self.app_state = AppState::AwaitingPieceSelection;
+        }
+    }
+
+    fn evaluate_command_key(&mut self, key: VirtualKeyCode) {
+        let mut chosen_move = None;
+        if let AppState::EnteringMove { command_line } = &mut self.app_state {
+            match key {
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter
+                    if command_line.input.text.starts_with("chat ") =>
+                {
+                    let text = command_line.input.text["chat ".len()..].to_string();
+                    match &mut self.network {
+                        Some(network) => network.send(Message::Chat(text)),
+                        None => {
+                            command_line.message = Some("Not a network game".to_string());
+                            return;
+                        }
+                    }
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                    match command_line.chosen_candidate() {
+                        Some(candidate) => {
+                            chosen_move = Some((candidate.chess_move.clone(), candidate.promotion))
+                        }
+                        None => {
+                            let text = command_line.input.text.trim();
+                            command_line.message = Some(format!("Unknown move: {}", text));
+                            return;
+                        }
+                    }
+                }
+                VirtualKeyCode::Tab => return command_line.complete(),
+                VirtualKeyCode::Back => return command_line.input.backspace(),
+                VirtualKeyCode::Escape => {}
+                _ => return,
+            }
+        }
+        self.app_state = AppState::AwaitingPieceSelection;
+        if let Some((chess_move, promotion)) = chosen_move {
+            if self.is_human_turn() {
+                self.execute_move_choice(&chess_move, promotion);
+            }
+        }
+    }
+
+    fn evaluate_menu_key(&mut self, key: VirtualKeyCode) {
+        if let AppState::InMenu { menu } = &mut self.app_state {
+            match key {
+                VirtualKeyCode::Up => menu.select_previous(),
+                VirtualKeyCode::Down => menu.select_next(),
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter | VirtualKeyCode::Space => {
+                    let item = menu.selected_item();
+                    self.activate_menu_item(item);
+                }
+                VirtualKeyCode::Escape => {
+                    let has_returned = menu.back();
+                    if !has_returned && menu.screen() == MenuScreen::InGame {
+                        self.app_state = AppState::AwaitingPieceSelection;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /** Select the menu item with a given index and activate it, e.g. after a click onto it. */
+    fn evaluate_menu_click(&mut self, index: usize) {
+        if let AppState::InMenu { menu } = &mut self.app_state {
+            menu.select(index);
+            let item = menu.selected_item();
+            self.activate_menu_item(item);
+        }
+    }
+
+    /** Perform the action of a given menu item. Items with a value change it to the next one. */
+    fn activate_menu_item(&mut self, item: MenuItem) {
+        match item {
+            MenuItem::NewGame if self.network.is_some() => {
+                self.show_menu_message("Not possible in a network game".to_string())
+            }
+            MenuItem::NewGame => self.open_menu_screen(MenuScreen::NewGame),
+            MenuItem::Settings => self.open_menu_screen(MenuScreen::Settings),
+            MenuItem::DeclineDraw if self.network.is_some() => {
+                self.send_to_network(Message::DeclineDraw);
+                self.app_state = AppState::AwaitingPieceSelection;
+            }
+            MenuItem::Back | MenuItem::DeclineDraw => {
+                if let AppState::InMenu { menu } = &mut self.app_state {
+                    menu.back();
+                }
+            }
+            MenuItem::LoadGame => match self.load_pgn() {
+                true => self.app_state = AppState::AwaitingPieceSelection,
+                false => {
+                    let pgn_file = &self.settings.pgn_file;
+                    self.show_menu_message(format!("Cannot load {}", pgn_file))
+                }
+            },
+            MenuItem::SetupPosition => self.open_position_setup(),
+            MenuItem::Quit => self.quit = true,
+            MenuItem::Mode => cycle_mode(&mut self.settings),
+            MenuItem::Color => {
+                if human_color(&self.settings).is_some() {
+                    swap_colors(&mut self.settings)
+                }
+            }
+            MenuItem::TimeControl => cycle_time_control(&mut self.settings),
+            MenuItem::Variant => match (&self.settings.fen, &self.position_fen) {
+                (Some(_), _) => self.settings.fen = None,
+                (None, Some(fen)) => self.settings.fen = Some(fen.clone()),
+                (None, None) => self.open_position_setup(),
+            },
+            MenuItem::Start => self.start_new_game(),
+            MenuItem::Theme => {
+                self.themes.select_next();
+                self.settings.theme = Some(self.themes.selected().name.clone());
+            }
+            MenuItem::SearchDepth => {
+                cycle_search_depth(&mut self.settings);
+                self.computer = ComputerPlayer::new(self.settings.search_depth);
+                self.analysis.set_search_depth(self.settings.search_depth);
+            }
+            MenuItem::Resume => self.app_state = AppState::AwaitingPieceSelection,
+            MenuItem::Resign | MenuItem::OfferDraw if self.is_game_over() => {
+                self.show_menu_message("The game is over".to_string())
+            }
+            MenuItem::Resign | MenuItem::OfferDraw if self.is_spectator() => {
+                self.show_menu_message("Spectators cannot play".to_string())
+            }
+            MenuItem::Resign => {
+                let color = human_color(&self.settings).unwrap_or(self.game.next_move_color());
+                self.send_to_network(Message::Resign);
+                self.game_end = Some(GameEnd::Resignation(color));
+                self.app_state = AppState::AwaitingPieceSelection;
+            }
+            MenuItem::OfferDraw if self.network.is_some() => {
+                self.send_to_network(Message::OfferDraw);
+                self.network_message = Some("Draw offered".to_string());
+                self.app_state = AppState::AwaitingPieceSelection;
+            }
+            MenuItem::OfferDraw => match human_color(&self.settings) {
+                // The computer accepts a draw unless it is ahead in material.
+                Some(color) if self.game.material_advantage(color.opponent()) > 0 => {
+                    self.show_menu_message("The computer declines the draw".to_string())
+                }
+                Some(_) => {
+                    self.game_end = Some(GameEnd::DrawAgreement);
+                    self.app_state = AppState::AwaitingPieceSelection;
+                }
+                None => self.open_menu_screen(MenuScreen::DrawOffer),
+            },
+            MenuItem::AcceptDraw => {
+                self.send_to_network(Message::AcceptDraw);
+                self.game_end = Some(GameEnd::DrawAgreement);
+                self.app_state = AppState::AwaitingPieceSelection;
+            }
+        }
+    }
+
+    /** Show a given screen of the menu which returns to the current one, if the menu is shown. */
+    fn open_menu_screen(&mut self, screen: MenuScreen) {
+        if let AppState::InMenu { menu } = &mut self.app_state {
+            menu.open(screen);
+        }
+    }
+
+    /** Show a given message below the items of the menu, if it is shown. */
+    fn show_menu_message(&mut self, message: String) {
+        if let AppState::InMenu { menu } = &mut self.app_state {
+            menu.message = Some(message);
+        }
+    }
+
+    /** Start entering the FEN of a position, initially the one of the current position. */
+    fn open_position_setup(&mut self) {
+        self.app_state = AppState::SettingUpPosition {
+            input: TextInput::new(&self.game.fen()),
+            message: None,
+        };
+    }
+
+    /** The position of the FEN entered for the setup of a position, if it is valid. */
+    fn setup_position(&self) -> Option<ChessGame> {
+        match &self.app_state {
+            AppState::SettingUpPosition { input, .. } => ChessGame::from_fen(&input.text).ok(),
+            _ => None,
+        }
+    }
+
+    fn evaluate_setup_key(&mut self, key: VirtualKeyCode) {
+        if let AppState::SettingUpPosition { input, message } = &mut self.app_state {
+            match key {
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                    let fen = input.text.trim().to_string();
+                    if let Err(error) = ChessGame::from_fen(&fen) {
+                        *message = Some(error);
+                        return;
+                    }
+                    self.settings.fen = Some(fen.clone());
+                    self.position_fen = Some(fen);
+                    let mut menu = Menu::new(MenuScreen::Main);
+                    menu.open(MenuScreen::NewGame);
+                    self.app_state = AppState::InMenu { menu };
+                }
+                VirtualKeyCode::Back => {
+                    input.backspace();
+                    *message = None;
+                }
+                VirtualKeyCode::Delete => {
+                    input.text.clear();
+                    *message = None;
+                }
+                VirtualKeyCode::Escape => {
+                    self.app_state = AppState::InMenu {
+                        menu: Menu::new(MenuScreen::Main),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /** Move the board cursor or select the square below it. Returns whether the key was handled. */
+    fn evaluate_cursor_key(&mut self, key: VirtualKeyCode) -> bool {
+        let Some((x, y)) = self.board_cursor else {
+            return false;
+        };
+        let screen_delta = match key {
+            VirtualKeyCode::Left => (-1, 0),
+            VirtualKeyCode::Right => (1, 0),
+            VirtualKeyCode::Up => (0, -1),
+            VirtualKeyCode::Down => (0, 1),
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                self.evaluate_mouse_click((x, y));
+                return true;
+            }
+            VirtualKeyCode::Escape => {
+                match self.app_state {
+                    AppState::AwaitingMoveSelection { .. } => {
+                        self.app_state = AppState::AwaitingPieceSelection
+                    }
+                    _ => self.board_cursor = None,
+                }
+                return true;
+            }
+            _ => return false,
+        };
+        let game = self.displayed_game();
+        let delta = self.view(&game).board_delta(screen_delta);
+        self.board_cursor = Some(((x + delta.0).clamp(0, 7), (y + delta.1).clamp(0, 7)));
+        true
+    }
+
+    /** The coordinate where the board cursor appears: The king of the color to move next. */
+    fn initial_cursor(&self) -> (i8, i8) {
+        let color = self.game.next_move_color();
+        self.game
+            .pieces
+            .iter()
+            .find(|piece| piece.piece_type == PieceType::King && piece.color == color)
+            .map(|king| king.square.position())
+            .unwrap_or((4, 4))
+    }
+
+    /** Execute a given move and promote to a given piece type, if the move is a promotion. */
+    fn execute_move_choice(&mut self, chess_move: &Move, promotion: Option<PieceType>) {
+        if self.is_game_over() {
+            return;
+        }
+        self.game.execute_move(chess_move);
+        if let Some(new_type) = promotion {
+            self.game.exchange_promotion_pawn(new_type);
+        }
+    }
+
+    /** Append a given typed character to the text currently edited, if any. */
+    fn evaluate_character(&mut self, c: char) {
+        match &mut self.app_state {
+            AppState::EditingComment { input, .. } => input.push(c),
+            AppState::EnteringMove { command_line } => {
+                command_line.input.push(c);
+                command_line.message = None;
+            }
+            AppState::SettingUpPosition { input, message } => {
+                input.push(c);
+                *message = None;
+            }
+            _ => {}
+        }
+    }
+
+    /** Add or remove a given Numeric Annotation Glyph to the move of the displayed position. */
+    fn toggle_nag(&mut self, nag: u8) {
+        let node = self.displayed_node();
+        if node != self.game.move_tree().root() {
+            self.game.annotation_mut(node).toggle_nag(nag);
+        }
+    }
+
+    /** Start drawing a marker of the displayed position at a given coordinate of the board. */
+    fn evaluate_marker_start(&mut self, coord: (i8, i8)) {
+        if self.is_paused() {
+            return;
+        }
+        self.marker_start = self.game.board.square_at(coord).copied();
+    }
+
+    /** Finish drawing a marker of the displayed position at a given coordinate of the board: An
+    arrow if the coordinate differs from the start, a highlighted square otherwise. */
+    fn evaluate_marker_end(&mut self, coord: (i8, i8), color: MarkerColor) {
+        let start = self.marker_start.take();
+        let end = self.game.board.square_at(coord).copied();
+        if let (Some(from), Some(to)) = (start, end) {
+            let annotation = self.game.annotation_mut(self.displayed_node());
+            match from == to {
+                true => annotation.toggle_square(SquareMarker { color, square: to }),
+                false => annotation.toggle_arrow(Arrow { color, from, to }),
+            }
+        }
+    }
+
+    /** Show the position after a given number of moves of the displayed line, e.g. after a click
+    into the move list. The line consists of the executed moves followed by their continuation. */
+    fn evaluate_move_list_click(&mut self, ply: usize) {
+        let move_count = self.game.executed_moves().len();
+        if ply <= move_count {
+            self.replay.jump_to(ply, move_count);
+        } else {
+            let tree = self.game.move_tree();
+            let node = tree.continuation(self.game.current_node())[ply - move_count - 1];
+            self.go_to_node(node);
+        }
+        self.app_state = AppState::AwaitingPieceSelection;
+    }
+
+    /** Save the game to the PGN file of the settings. */
+    fn save_pgn(&mut self) {
+        let pgn_file = &self.settings.pgn_file;
+        match fs::write(pgn_file, export_pgn(&self.game)) {
+            Ok(_) => self.status.show(format!("Game saved to {}", pgn_file)),
+            Err(error) => self
+                .status
+                .show(format!("Cannot save game to {}: {}", pgn_file, error)),
+        }
+    }
+
+    /** Export the displayed position as seen on the screen, including the arrows and highlighted
+    squares of its annotation, to an SVG and a PNG file. */
+    fn export_diagram(&mut self) {
+        let game = self.displayed_game();
+        let theme = self.themes.selected();
+        let annotation = &self.game.move_tree().node(self.displayed_node()).annotation;
+        let options = DiagramOptions {
+            squares: annotation.squares.clone(),
+            arrows: annotation.arrows.clone(),
+            colors: theme.diagram_colors(),
+            ..DiagramOptions::for_game(&game, self.bottom_color(&game))
+        };
+        let svg = fs::write(DIAGRAM_SVG_FILE, export_svg(&game, &options))
+            .map_err(|error| format!("{}: {}", DIAGRAM_SVG_FILE, error));
+        let resource_dir = &self.settings.resource_dir;
+        let pieces_path = theme.pieces_font.path(resource_dir);
+        let png = PieceSprites::load(Path::new(&pieces_path), theme.pieces_font.width)
+            .or_else(|_| PieceSprites::load_default(resource_dir))
+            .and_then(|sprites| export_png(&game, &options, &sprites))
+            .and_then(|png| fs::write(DIAGRAM_PNG_FILE, png).map_err(|error| error.to_string()))
+            .map_err(|error| format!("{}: {}", DIAGRAM_PNG_FILE, error));
+        match svg.and(png) {
+            Ok(_) => self.status.show(format!(
+                "Diagram saved to {} and {}",
+                DIAGRAM_SVG_FILE, DIAGRAM_PNG_FILE
+            )),
+            Err(error) => self
+                .status
+                .show(format!("Cannot save diagram to {}", error)),
+        }
+    }
+
+    /** Export an animation of all moves of the game to a GIF file. The animation is encoded in the
+    background, which takes a few seconds for longer games. */
+    fn export_animation(&mut self) {
+        let game = self.game.clone();
+        let theme = self.themes.selected();
+        let options = DiagramOptions {
+            colors: theme.diagram_colors(),
+            ..DiagramOptions::for_game(&game, self.bottom_color(&game))
+        };
+        let resource_dir = self.settings.resource_dir.clone();
+        let pieces_path = theme.pieces_font.path(&resource_dir);
+        let tile_size = theme.pieces_font.width;
+        self.status
+            .show(format!("Exporting game to {}...", ANIMATION_FILE));
+        let status = self.status.sender();
+        thread::spawn(move || {
+            let gif = PieceSprites::load(Path::new(&pieces_path), tile_size)
+                .or_else(|_| PieceSprites::load_default(&resource_dir))
+                .and_then(|sprites| export_gif(&game, &options, &sprites, DEFAULT_FRAME_DELAY_MS))
+                .and_then(|gif| fs::write(ANIMATION_FILE, gif).map_err(|error| error.to_string()));
+            let message = match gif {
+                Ok(_) => format!("Game saved to {}", ANIMATION_FILE),
+                Err(error) => format!("Cannot save game to {}: {}", ANIMATION_FILE, error),
+            };
+            let _ = status.send(message);
+        });
+    }
+
+    /** Replace the game by the one of the PGN file of the settings. Returns whether the file
+    could be loaded. */
+    fn load_pgn(&mut self) -> bool {
+        let pgn_file = self.settings.pgn_file.clone();
+        let loaded = fs::read_to_string(&pgn_file)
+            .map_err(|error| error.to_string())
+            .and_then(|text| import_pgn(&text));
+        match loaded {
+            Ok(game) => {
+                self.reset_game(game);
+                self.status.show(format!("Game loaded from {}", pgn_file));
+                true
+            }
+            Err(error) => {
+                self.status
+                    .show(format!("Cannot load game from {}: {}", pgn_file, error));
+                false
+            }
+        }
+    }
+
+    /** The move the user currently prepares, either by clicking or by dragging a piece. */
+    fn user_move(&self) -> Option<&UserMove> {
+        match &self.app_state {
+            AppState::AwaitingMoveSelection { user_move }
+            | AppState::DraggingPiece { user_move } => Some(user_move),
+            _ => None,
+        }
+    }
+
+    /** Press the mouse button at a given board coordinate: Selecting a piece starts dragging it,
+    everything else is handled like a click. */
+    fn evaluate_mouse_press(&mut self, coord: (i8, i8)) {
+        let was_awaiting_piece = matches!(self.app_state, AppState::AwaitingPieceSelection);
+        self.evaluate_mouse_click(coord);
+        if !was_awaiting_piece {
+            return;
+        }
+        if let AppState::AwaitingMoveSelection { user_move } =
+            mem::replace(&mut self.app_state, AppState::AwaitingPieceSelection)
+        {
+            self.app_state = AppState::DraggingPiece { user_move };
+        }
+    }
+
+    /** Release the mouse button at a given board coordinate: A dragged piece is dropped there. If
+    it is dropped onto its own square, the target may still be chosen by a click. */
+    fn evaluate_mouse_release(&mut self, coord: (i8, i8)) {
+        if let AppState::DraggingPiece { user_move } =
+            mem::replace(&mut self.app_state, AppState::AwaitingPieceSelection)
+        {
+            let is_own_square = user_move.piece.square.position() == coord;
+            self.app_state = AppState::AwaitingMoveSelection { user_move };
+            if !is_own_square {
+                self.evaluate_mouse_click(coord);
+            }
         }
     }
 
     fn evaluate_mouse_click(&mut self, coord: (i8, i8)) {
+        if self.is_spectator() {
+            return;
+        }
+        if self.game.board.square_at(coord).is_some() && !self.take_over_displayed_position() {
+            return;
+        }
         match &self.app_state {
             AppState::AwaitingPieceSelection => {
                 if let Some(selected_piece) = self.game.piece_at(coord) {
                     let promo_pawn = self.game.promotion_pawn();
-                    if promo_pawn.is_some() {
+                    if promo_pawn.is_some() && self.is_human_turn() {
                         if selected_piece.square == promo_pawn.unwrap().square {
                             self.game.exchange_promotion_pawn(PieceType::Queen);
                             return;
@@ -46,20 +1097,23 @@ impl MainState {
                     self.app_state = AppState::AwaitingMoveSelection {
                         user_move: UserMove::new(selected_piece.clone(), possible_moves),
                     };
-                } else if let Some(square) = self.game.board.square_at(coord) {
-                    let challengers = self.game.square_challengers(square);
-                    println!("Challengers for {}: {:?}", square, challengers);
-                    println!("Can move = {:?}", self.game.can_move(&PieceColor::White));
                 }
             }
-            AppState::AwaitingMoveSelection { user_move } => {
+            AppState::AwaitingMoveSelection { user_move }
+            | AppState::DraggingPiece { user_move } => {
                 if let Some(selected_target) = self.game.board.square_at(coord) {
                     if let Some(chosen_move) = user_move.possible_move_to_target(*selected_target) {
-                        self.game.execute_move(chosen_move);
+                        if self.is_human_turn() {
+                            self.game.execute_move(chosen_move);
+                        }
                     }
                 }
                 self.app_state = AppState::AwaitingPieceSelection;
             }
+            AppState::EditingComment { .. }
+            | AppState::EnteringMove { .. }
+            | AppState::InMenu { .. }
+            | AppState::SettingUpPosition { .. } => {}
         }
     }
 }
@@ -0,0 +1,18 @@
+pub mod protocol;
+pub mod session;
+
+/** The version of the protocol, which has to be the same on both sides. */
+pub const PROTOCOL_VERSION: u32 = 1;
+/** The TCP port used if none is given. */
+pub const DEFAULT_PORT: u16 = 7878;
+
+/** How a game is connected to the game of the opponent. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkRole {
+    /** Wait for the opponent on a given TCP port. The host decides on the colors and the position. */
+    Host(u16),
+    /** Connect to a given address of a host, e.g. "192.168.0.2:7878" or "localhost". */
+    Guest(String),
+    /** Watch the game of the host of a given address without playing. */
+    Spectator(String),
+}
@@ -0,0 +1,150 @@
+use std::fmt::{Display, Formatter};
+
+use crate::domain::pieces::PieceColor;
+
+/** A message exchanged between two networked games. Each message is sent as a single line of text
+which starts with a keyword, e.g. "move e2e4" or "chat Good luck!". */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /** The first message of each side after connecting, with the version of the protocol. The host
+    also tells the guest its color, e.g. "hello 1 black". */
+    Hello {
+        version: u32,
+        color: Option<PieceColor>,
+    },
+    /** The first message of a spectator after connecting to the host, e.g. "watch 1". */
+    Watch {
+        version: u32,
+    },
+    /** A move in coordinate notation as used by chess engines, e.g. "move e7e8q". */
+    Move(String),
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+    Resign,
+    Chat(String),
+    /** The position the game continues with in Forsyth-Edwards Notation, sent by the host after
+    each connect. Without a position it is a request of the guest to send it again. */
+    Sync(Option<String>),
+    /** The initial position of the game in Forsyth-Edwards Notation followed by all moves played
+    since, sent by the host to spectators, e.g. "position fen <FEN> moves e2e4 e7e5". */
+    Position {
+        fen: String,
+        moves: Vec<String>,
+    },
+    /** The resignation of a player of a given color, sent by the host to spectators. */
+    Resigned(PieceColor),
+    /** The agreement of the players on a draw, sent by the host to spectators. */
+    DrawAgreed,
+    /** A problem with a message received before, e.g. an impossible move. */
+    Error(String),
+}
+
+impl Message {
+    /** The message of a given line of text. */
+    pub fn parse(line: &str) -> Result<Message, String> {
+        let line = line.trim();
+        let (keyword, argument) = match line.split_once(' ') {
+            Some((keyword, argument)) => (keyword, argument.trim()),
+            None => (line, ""),
+        };
+        let result = match (keyword, argument) {
+            ("hello", _) => {
+                let mut words = argument.split_whitespace();
+                let version = words
+                    .next()
+                    .and_then(|word| word.parse().ok())
+                    .ok_or_else(|| format!("Invalid hello '{}'", line))?;
+                let color = match words.next() {
+                    Some(word) => {
+                        Some(parse_color(word).ok_or_else(|| format!("Invalid hello '{}'", line))?)
+                    }
+                    None => None,
+                };
+                Message::Hello { version, color }
+            }
+            ("watch", _) => Message::Watch {
+                version: argument
+                    .parse()
+                    .map_err(|_| format!("Invalid watch '{}'", line))?,
+            },
+            ("position", _) => {
+                let fen_and_moves = argument
+                    .strip_prefix("fen ")
+                    .ok_or_else(|| format!("Invalid position '{}'", line))?;
+                let (fen, moves) = match fen_and_moves.split_once(" moves") {
+                    Some((fen, moves)) => (fen, moves.split_whitespace().collect()),
+                    None => (fen_and_moves, Vec::new()),
+                };
+                Message::Position {
+                    fen: fen.trim().to_string(),
+                    moves: moves.iter().map(|text| text.to_string()).collect(),
+                }
+            }
+            ("resigned", _) => Message::Resigned(
+                parse_color(argument).ok_or_else(|| format!("Invalid color in '{}'", line))?,
+            ),
+            ("draw-agreed", "") => Message::DrawAgreed,
+            ("move", "") => return Err("Missing move".to_string()),
+            ("move", _) => Message::Move(argument.to_string()),
+            ("offer-draw", "") => Message::OfferDraw,
+            ("accept-draw", "") => Message::AcceptDraw,
+            ("decline-draw", "") => Message::DeclineDraw,
+            ("resign", "") => Message::Resign,
+            ("chat", _) => Message::Chat(argument.to_string()),
+            ("sync", "") => Message::Sync(None),
+            ("sync", _) => Message::Sync(Some(argument.to_string())),
+            ("error", _) => Message::Error(argument.to_string()),
+            _ => return Err(format!("Unknown message '{}'", line)),
+        };
+        Ok(result)
+    }
+}
+
+impl Display for Message {
+    /** The line of text of this message, without a line break. */
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Message::Hello {
+                version,
+                color: None,
+            } => write!(f, "hello {}", version),
+            Message::Hello {
+                version,
+                color: Some(color),
+            } => write!(f, "hello {} {}", version, color_name(*color)),
+            Message::Watch { version } => write!(f, "watch {}", version),
+            Message::Move(coordinates) => write!(f, "move {}", coordinates),
+            Message::OfferDraw => write!(f, "offer-draw"),
+            Message::AcceptDraw => write!(f, "accept-draw"),
+            Message::DeclineDraw => write!(f, "decline-draw"),
+            Message::Resign => write!(f, "resign"),
+            // Line breaks would split the message.
+            Message::Chat(text) => write!(f, "chat {}", text.replace(['\r', '\n'], " ")),
+            Message::Sync(None) => write!(f, "sync"),
+            Message::Sync(Some(fen)) => write!(f, "sync {}", fen),
+            Message::Position { fen, moves } if moves.is_empty() => {
+                write!(f, "position fen {}", fen)
+            }
+            Message::Position { fen, moves } => {
+                write!(f, "position fen {} moves {}", fen, moves.join(" "))
+            }
+            Message::Resigned(color) => write!(f, "resigned {}", color_name(*color)),
+            Message::DrawAgreed => write!(f, "draw-agreed"),
+            Message::Error(text) => write!(f, "error {}", text.replace(['\r', '\n'], " ")),
+        }
+    }
+}
+
+fn color_name(color: PieceColor) -> &'static str {
+    match color {
+        PieceColor::White => "white",
+        PieceColor::Black => "black",
+    }
+}
+
+fn parse_color(text: &str) -> Option<PieceColor> {
+    [PieceColor::White, PieceColor::Black]
+        .into_iter()
+        .find(|color| color_name(*color) == text)
+}
@@ -0,0 +1,692 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::time::{Duration, Instant};
+use std::{mem, thread};
+
+use crate::domain::game::notation::coordinate_notation;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::{PieceColor, PieceType};
+use crate::network::protocol::Message;
+use crate::network::{DEFAULT_PORT, PROTOCOL_VERSION};
+
+/** How long a guest or spectator waits for the host to connect and to answer its first message. */
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/** How long a guest or spectator waits for the host when reconnecting, which blocks the game
+meanwhile. */
+const RECONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(3);
+/** The length of the longest line received, which is enough for the position message of any game.
+A longer line closes the connection. */
+const MAX_LINE_LENGTH: u64 = 64 * 1024;
+
+/** Something the opponent did or that happened to the connection, see [NetworkSession::poll]. */
+#[derive(Debug, Clone)]
+pub enum NetworkEvent {
+    Connected,
+    Disconnected,
+    /** A move of the opponent, or of either player for a spectator, which is possible in the local
+    game. */
+    Move(Move, Option<PieceType>),
+    DrawOffered,
+    DrawAccepted,
+    DrawDeclined,
+    /** The resignation of the player of a given color. */
+    Resigned(PieceColor),
+    Chat(String),
+    /** The position of the host, which replaces the local game of a guest or spectator. */
+    Synced(Box<ChessGame>),
+    SpectatorJoined,
+    SpectatorLeft,
+    /** A message which could not be understood or was rejected, e.g. an impossible move because
+    the games are out of sync. */
+    Error(String),
+}
+
+/** The part a session plays in a network game. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Host,
+    Guest,
+    Spectator,
+}
+
+/** A connection sending and receiving messages as lines of text. The lines are written and read by
+threads of their own, so neither sending nor receiving blocks. */
+struct Connection {
+    messages: Sender<String>,
+    lines: Receiver<String>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Self, String> {
+        stream
+            .set_nodelay(true)
+            .map_err(|error| error.to_string())?;
+        let reader = stream.try_clone().map_err(|error| error.to_string())?;
+        let (sender, lines) = channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let mut line = String::new();
+                let Ok(length) = (&mut reader).take(MAX_LINE_LENGTH).read_line(&mut line) else {
+                    break;
+                };
+                // The end of the stream, or a line which is too long to be a message.
+                if length == 0 || !line.ends_with('\n') {
+                    break;
+                }
+                let line = line.trim_end_matches(['\r', '\n']).to_string();
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let (messages, outgoing) = channel::<String>();
+        let mut writer = stream;
+        thread::spawn(move || {
+            for message in outgoing {
+                if writeln!(writer, "{}", message)
+                    .and_then(|_| writer.flush())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            // Closing the stream once the connection is dropped also ends the reading thread.
+            let _ = writer.shutdown(Shutdown::Both);
+        });
+        Ok(Self { messages, lines })
+    }
+
+    /** Send a given message. A failure shows as a closed connection when receiving. */
+    fn send(&mut self, message: &Message) {
+        let _ = self.messages.send(message.to_string());
+    }
+}
+
+/** The connection of a local game to the game of the opponent on another computer. The local game
+is not changed by the session: Moves of the opponent are validated and reported by [Self::poll],
+local moves are sent as soon as they are complete, i.e. including the promotion. The host also
+sends all moves to any number of spectators, who cannot play themselves. */
+pub struct NetworkSession {
+    role: Role,
+    /** The color of the local player, none for a spectator. */
+    local_color: Option<PieceColor>,
+    /** The socket on which the host accepts the guest, also when it reconnects, and spectators. */
+    listener: Option<TcpListener>,
+    /** Connections accepted by the host which have not told yet whether they play or watch. */
+    pending: Vec<Connection>,
+    spectators: Vec<Connection>,
+    /** The address of the host to which the guest or spectator reconnects. */
+    host_address: Option<SocketAddr>,
+    /** The connection to the opponent, or to the host for a spectator. */
+    connection: Option<Connection>,
+    last_connect_attempt: Instant,
+    /** The number of executed moves of the local game the opponent knows about. */
+    known_ply: usize,
+}
+
+impl NetworkSession {
+    /** A session waiting for a guest and spectators on a given TCP port. The local player plays a
+    given color. */
+    pub fn host(port: u16, local_color: PieceColor) -> Result<Self, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|error| format!("Cannot listen on port {}: {}", port, error))?;
+        Ok(Self::new(
+            Role::Host,
+            Some(local_color),
+            Some(listener),
+            None,
+            None,
+        ))
+    }
+
+    /** A session connected to the host of a given address, together with the position the host
+    wants to play. Waits until the host has answered. The port is optional. */
+    pub fn connect(address: &str) -> Result<(Self, ChessGame), String> {
+        let host_address = resolve(address)?;
+        let mut connection = open_connection(&host_address, CONNECT_TIMEOUT)
+            .map_err(|error| format!("Cannot connect to {}: {}", address, error))?;
+        connection.send(&hello(None));
+        let mut local_color = None;
+        loop {
+            match receive_answer(&connection, address)? {
+                Message::Hello { version, color } => {
+                    check_version(version)?;
+                    local_color = color;
+                }
+                Message::Sync(Some(fen)) => {
+                    let game = ChessGame::from_fen(&fen)?;
+                    let local_color =
+                        local_color.ok_or_else(|| "The host did not assign a color".to_string())?;
+                    let session = Self::new(
+                        Role::Guest,
+                        Some(local_color),
+                        None,
+                        Some(host_address),
+                        Some(connection),
+                    );
+                    return Ok((session, game));
+                }
+                Message::Error(text) => return Err(format!("The host reports: {}", text)),
+                _ => {}
+            }
+        }
+    }
+
+    /** A session watching the game of the host of a given address, together with the game played
+    so far. Waits until the host has answered. The port is optional. */
+    pub fn watch(address: &str) -> Result<(Self, ChessGame), String> {
+        let host_address = resolve(address)?;
+        let mut connection = open_connection(&host_address, CONNECT_TIMEOUT)
+            .map_err(|error| format!("Cannot connect to {}: {}", address, error))?;
+        connection.send(&watch());
+        loop {
+            match receive_answer(&connection, address)? {
+                Message::Hello { version, .. } => check_version(version)?,
+                Message::Position { fen, moves } => {
+                    let game = replay_position(&fen, &moves)?;
+                    let mut session = Self::new(
+                        Role::Spectator,
+                        None,
+                        None,
+                        Some(host_address),
+                        Some(connection),
+                    );
+                    session.known_ply = game.executed_moves().len();
+                    return Ok((session, game));
+                }
+                Message::Error(text) => return Err(format!("The host reports: {}", text)),
+                _ => {}
+            }
+        }
+    }
+
+    fn new(
+        role: Role,
+        local_color: Option<PieceColor>,
+        listener: Option<TcpListener>,
+        host_address: Option<SocketAddr>,
+        connection: Option<Connection>,
+    ) -> Self {
+        Self {
+            role,
+            local_color,
+            listener,
+            pending: Vec::new(),
+            spectators: Vec::new(),
+            host_address,
+            connection,
+            last_connect_attempt: Instant::now(),
+            known_ply: 0,
+        }
+    }
+
+    /** The color of the local player, none for a spectator. */
+    pub fn local_color(&self) -> Option<PieceColor> {
+        self.local_color
+    }
+    /** Whether this session waits for guests instead of connecting to a host. */
+    pub fn is_host(&self) -> bool {
+        self.role == Role::Host
+    }
+    /** Whether this session only watches the game of a host. */
+    pub fn is_spectator(&self) -> bool {
+        self.role == Role::Spectator
+    }
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+    /** The number of spectators watching the game of the host. */
+    pub fn spectator_count(&self) -> usize {
+        self.spectators.len()
+    }
+
+    /** Send a given message to the opponent, if connected. The host tells spectators about a
+    resignation or an agreed draw. A spectator sends nothing. */
+    pub fn send(&mut self, message: Message) {
+        if self.is_spectator() {
+            return;
+        }
+        match (&message, self.local_color) {
+            (Message::Resign, Some(color)) => self.broadcast(&Message::Resigned(color)),
+            (Message::AcceptDraw, _) => self.broadcast(&Message::DrawAgreed),
+            _ => {}
+        }
+        if let Some(connection) = &mut self.connection {
+            connection.send(&message);
+        }
+    }
+    /** Send the moves of a given local game which the opponent and the spectators do not know yet.
+    A promotion is sent once the new piece type is chosen. Also done by [Self::poll]. */
+    pub fn send_moves(&mut self, game: &ChessGame) {
+        let moves = game.executed_moves();
+        // Moves taken back locally are unknown to the opponent, who will reject the next move.
+        self.known_ply = self.known_ply.min(moves.len());
+        if game.promotion_pawn().is_some() || self.is_spectator() {
+            return;
+        }
+        while let Some(executed_move) = moves.get(self.known_ply) {
+            let chess_move = Move::new(executed_move.piece, executed_move.target_square);
+            let message = Message::Move(coordinate_notation(&chess_move, executed_move.promotion));
+            self.broadcast(&message);
+            self.send(message);
+            self.known_ply += 1;
+        }
+    }
+
+    /** The events since the last call for a local game in a given state. Reconnects if the
+    connection was lost and sends new local moves. At most one event changes the game, and it is
+    the last one, so the next call has to pass the changed game. */
+    pub fn poll(&mut self, game: &ChessGame) -> Vec<NetworkEvent> {
+        let mut result = Vec::new();
+        match self.role {
+            Role::Host => {
+                result.extend(self.accept(game));
+                result.extend(self.poll_spectators(game));
+            }
+            Role::Guest | Role::Spectator => {
+                if self.connection.is_none() && self.reconnect() {
+                    result.push(NetworkEvent::Connected);
+                }
+            }
+        }
+        self.send_moves(game);
+        while let Some(connection) = &self.connection {
+            let event = match connection.lines.try_recv() {
+                Ok(line) => match Message::parse(&line) {
+                    Ok(message) => self.receive(message, game),
+                    Err(error) => Some(NetworkEvent::Error(error)),
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.connection = None;
+                    Some(NetworkEvent::Disconnected)
+                }
+            };
+            let changes_game = matches!(
+                event,
+                Some(NetworkEvent::Move(..)) | Some(NetworkEvent::Synced(_))
+            );
+            result.extend(event);
+            if changes_game {
+                break;
+            }
+        }
+        result
+    }
+
+    /** Send a given message to all spectators. */
+    fn broadcast(&mut self, message: &Message) {
+        for spectator in &mut self.spectators {
+            spectator.send(message);
+        }
+    }
+
+    /** Accept new connections of the host and let them play or watch, depending on their first
+    message, for a given local game. Only one of them can play. */
+    fn accept(&mut self, game: &ChessGame) -> Vec<NetworkEvent> {
+        let mut result = Vec::new();
+        if let Some(listener) = &self.listener {
+            while let Ok((stream, _)) = listener.accept() {
+                // An accepted stream may inherit the non-blocking mode of the listener.
+                let connection = stream
+                    .set_nonblocking(false)
+                    .map_err(|error| error.to_string())
+                    .and_then(|_| Connection::new(stream));
+                if let Ok(connection) = connection {
+                    self.pending.push(connection);
+                }
+            }
+        }
+        for mut connection in mem::take(&mut self.pending) {
+            let message = match connection.lines.try_recv() {
+                Ok(line) => Message::parse(&line),
+                Err(TryRecvError::Empty) => {
+                    self.pending.push(connection);
+                    continue;
+                }
+                Err(TryRecvError::Disconnected) => continue,
+            };
+            let version = match message {
+                Ok(Message::Hello { version, .. }) | Ok(Message::Watch { version }) => version,
+                _ => continue,
+            };
+            if let Err(error) = check_version(version) {
+                connection.send(&Message::Error(error.clone()));
+                result.push(NetworkEvent::Error(error));
+                continue;
+            }
+            match message {
+                Ok(Message::Watch { .. }) => {
+                    connection.send(&hello(None));
+                    connection.send(&position(game));
+                    self.spectators.push(connection);
+                    result.push(NetworkEvent::SpectatorJoined);
+                }
+                _ if self.connection.is_some() => {
+                    let error = "The game already has two players, but it can be watched";
+                    connection.send(&Message::Error(error.to_string()));
+                }
+                _ => {
+                    let guest_color = self.local_color.map(|color| color.opponent());
+                    connection.send(&hello(guest_color));
+                    connection.send(&Message::Sync(Some(game.fen())));
+                    self.known_ply = game.executed_moves().len();
+                    self.connection = Some(connection);
+                    result.push(NetworkEvent::Connected);
+                }
+            }
+        }
+        result
+    }
+
+    /** Answer the requests of the spectators of the host for a given local game and drop the ones
+    which have left. */
+    fn poll_spectators(&mut self, game: &ChessGame) -> Vec<NetworkEvent> {
+        let mut result = Vec::new();
+        for mut spectator in mem::take(&mut self.spectators) {
+            let has_left = loop {
+                match spectator.lines.try_recv() {
+                    Ok(line) => {
+                        if let Ok(Message::Sync(None)) = Message::parse(&line) {
+                            spectator.send(&position(game));
+                        }
+                    }
+                    Err(TryRecvError::Empty) => break false,
+                    Err(TryRecvError::Disconnected) => break true,
+                }
+            };
+            match has_left {
+                true => result.push(NetworkEvent::SpectatorLeft),
+                false => self.spectators.push(spectator),
+            }
+        }
+        result
+    }
+
+    /** Reconnect a guest or spectator to the host. Returns whether the connection was made. */
+    fn reconnect(&mut self) -> bool {
+        let Some(address) = self.host_address else {
+            return false;
+        };
+        if self.last_connect_attempt.elapsed() < RECONNECT_INTERVAL {
+            return false;
+        }
+        self.last_connect_attempt = Instant::now();
+        let Ok(mut connection) = open_connection(&address, RECONNECT_TIMEOUT) else {
+            return false;
+        };
+        match self.role {
+            Role::Spectator => connection.send(&watch()),
+            _ => connection.send(&hello(None)),
+        }
+        self.connection = Some(connection);
+        true
+    }
+
+    /** The event of a given message received for a given local game, answering it if necessary. */
+    fn receive(&mut self, message: Message, game: &ChessGame) -> Option<NetworkEvent> {
+        let remote_color = self.local_color.map(|color| color.opponent());
+        match message {
+            Message::Hello { version, .. } | Message::Watch { version } => {
+                match check_version(version) {
+                    Ok(_) => None,
+                    Err(error) => {
+                        self.send(Message::Error(error.clone()));
+                        self.connection = None;
+                        Some(NetworkEvent::Error(error))
+                    }
+                }
+            }
+            Message::Move(coordinates) => Some(self.receive_move(&coordinates, game)),
+            Message::OfferDraw => Some(NetworkEvent::DrawOffered),
+            Message::AcceptDraw => {
+                self.broadcast(&Message::DrawAgreed);
+                Some(NetworkEvent::DrawAccepted)
+            }
+            Message::DeclineDraw => Some(NetworkEvent::DrawDeclined),
+            Message::Resign => {
+                let color = remote_color?;
+                self.broadcast(&Message::Resigned(color));
+                Some(NetworkEvent::Resigned(color))
+            }
+            Message::Resigned(color) if self.is_spectator() => Some(NetworkEvent::Resigned(color)),
+            Message::DrawAgreed if self.is_spectator() => Some(NetworkEvent::DrawAccepted),
+            Message::Resigned(_) | Message::DrawAgreed => None,
+            Message::Chat(text) => Some(NetworkEvent::Chat(text)),
+            Message::Sync(None) if self.is_host() => {
+                self.send(Message::Sync(Some(game.fen())));
+                self.known_ply = game.executed_moves().len();
+                None
+            }
+            Message::Sync(Some(fen)) if !self.is_host() => match ChessGame::from_fen(&fen) {
+                Ok(synced_game) => {
+                    self.known_ply = 0;
+                    Some(NetworkEvent::Synced(Box::new(synced_game)))
+                }
+                Err(error) => Some(NetworkEvent::Error(error)),
+            },
+            Message::Position { fen, moves } if !self.is_host() => {
+                match replay_position(&fen, &moves) {
+                    Ok(synced_game) => {
+                        self.known_ply = synced_game.executed_moves().len();
+                        Some(NetworkEvent::Synced(Box::new(synced_game)))
+                    }
+                    Err(error) => Some(NetworkEvent::Error(error)),
+                }
+            }
+            // Only the host decides on the position.
+            Message::Sync(_) | Message::Position { .. } => None,
+            Message::Error(text) => Some(NetworkEvent::Error(format!(
+                "The opponent reports: {}",
+                text
+            ))),
+        }
+    }
+
+    /** The event of a move received for a given local game. The host passes it on to the
+    spectators. An impossible move is rejected and the position of the host is synchronized. */
+    fn receive_move(&mut self, coordinates: &str, game: &ChessGame) -> NetworkEvent {
+        let choice = match Some(game.next_move_color()) == self.local_color {
+            true => None,
+            false => find_move(game, coordinates),
+        };
+        if let Some((chess_move, promotion)) = choice {
+            self.broadcast(&Message::Move(coordinates.to_lowercase()));
+            self.known_ply = game.executed_moves().len() + 1;
+            return NetworkEvent::Move(chess_move, promotion);
+        }
+        let error = format!("Impossible move {} in {}", coordinates, game.fen());
+        match self.role {
+            Role::Host => {
+                self.send(Message::Error(error.clone()));
+                self.send(Message::Sync(Some(game.fen())));
+                self.known_ply = game.executed_moves().len();
+            }
+            Role::Guest => {
+                self.send(Message::Error(error.clone()));
+                self.send(Message::Sync(None));
+            }
+            // The spectator has missed a move, so it asks for the whole game again.
+            Role::Spectator => {
+                if let Some(connection) = &mut self.connection {
+                    connection.send(&Message::Sync(None));
+                }
+            }
+        }
+        NetworkEvent::Error(error)
+    }
+}
+
+fn hello(color: Option<PieceColor>) -> Message {
+    Message::Hello {
+        version: PROTOCOL_VERSION,
+        color,
+    }
+}
+
+fn watch() -> Message {
+    Message::Watch {
+        version: PROTOCOL_VERSION,
+    }
+}
+
+/** The message with the initial position and all moves of a given game. */
+fn position(game: &ChessGame) -> Message {
+    Message::Position {
+        fen: game.initial_position().fen(),
+        moves: game
+            .executed_moves()
+            .iter()
+            .map(|executed_move| {
+                let chess_move = Move::new(executed_move.piece, executed_move.target_square);
+                coordinate_notation(&chess_move, executed_move.promotion)
+            })
+            .collect(),
+    }
+}
+
+/** The game starting at the position of a given FEN with given moves in coordinate notation. */
+fn replay_position(fen: &str, moves: &[String]) -> Result<ChessGame, String> {
+    let mut game = ChessGame::from_fen(fen)?;
+    for coordinates in moves {
+        let (chess_move, promotion) = find_move(&game, coordinates)
+            .ok_or_else(|| format!("Impossible move {} in {}", coordinates, game.fen()))?;
+        game.execute_move(&chess_move);
+        if let Some(new_type) = promotion {
+            game.exchange_promotion_pawn(new_type);
+        }
+    }
+    Ok(game)
+}
+
+/** The possible move of a given game with given coordinates, e.g. "e7e8q". */
+fn find_move(game: &ChessGame, coordinates: &str) -> Option<(Move, Option<PieceType>)> {
+    let coordinates = coordinates.to_lowercase();
+    game.possible_move_choices()
+        .into_iter()
+        .find(|(chess_move, promotion)| coordinate_notation(chess_move, *promotion) == coordinates)
+}
+
+fn open_connection(address: &SocketAddr, timeout: Duration) -> Result<Connection, String> {
+    TcpStream::connect_timeout(address, timeout)
+        .map_err(|error| error.to_string())
+        .and_then(Connection::new)
+}
+
+/** The next message of the host of a given address, waiting for it at most the connect timeout. */
+fn receive_answer(connection: &Connection, address: &str) -> Result<Message, String> {
+    let line = connection
+        .lines
+        .recv_timeout(CONNECT_TIMEOUT)
+        .map_err(|_| format!("No answer from {}", address))?;
+    Message::parse(&line)
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    match version == PROTOCOL_VERSION {
+        true => Ok(()),
+        false => Err(format!(
+            "Protocol version {} of the opponent differs from version {}",
+            version, PROTOCOL_VERSION
+        )),
+    }
+}
+
+/** The socket address of a given host address, e.g. "localhost" or "192.168.0.2:7878". */
+fn resolve(address: &str) -> Result<SocketAddr, String> {
+    let with_port = match address.rsplit_once(':') {
+        Some((_, port)) if port.parse::<u16>().is_ok() => address.to_string(),
+        _ => format!("{}:{}", address, DEFAULT_PORT),
+    };
+    with_port
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addresses| addresses.next())
+        .ok_or_else(|| format!("Unknown address '{}'", address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLL_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /** Poll a given session for a given game until an event matches a given predicate. Returns all
+    events up to and including the matching one. */
+    fn poll_until(
+        session: &mut NetworkSession,
+        game: &ChessGame,
+        predicate: impl Fn(&NetworkEvent) -> bool,
+    ) -> Vec<NetworkEvent> {
+        let start = Instant::now();
+        let mut result = Vec::new();
+        while start.elapsed() < POLL_TIMEOUT {
+            result.extend(session.poll(game));
+            if result.iter().any(&predicate) {
+                return result;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("No matching event within the timeout, got {:?}", result);
+    }
+
+    fn execute(game: &mut ChessGame, coordinates: &str) {
+        let (chess_move, _) = find_move(game, coordinates).unwrap();
+        game.execute_move(&chess_move);
+    }
+
+    #[test]
+    fn host_and_guest_exchange_moves_errors_and_resignation() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap()
+            .port();
+        let mut host = NetworkSession::host(port, PieceColor::White).unwrap();
+        let mut host_game = ChessGame::new();
+        let guest = thread::spawn(move || NetworkSession::connect(&format!("127.0.0.1:{}", port)));
+        poll_until(&mut host, &host_game, |event| {
+            matches!(event, NetworkEvent::Connected)
+        });
+        let (mut guest, mut guest_game) = guest.join().unwrap().unwrap();
+        assert_eq!(guest.local_color(), Some(PieceColor::Black));
+        assert_eq!(guest_game.fen(), host_game.fen());
+
+        execute(&mut host_game, "e2e4");
+        host.send_moves(&host_game);
+        let events = poll_until(&mut guest, &guest_game, |event| {
+            matches!(event, NetworkEvent::Move(..))
+        });
+        let Some(NetworkEvent::Move(chess_move, None)) = events.last() else {
+            panic!("Expected a move, got {:?}", events);
+        };
+        guest_game.execute_move(chess_move);
+        assert_eq!(guest_game.fen(), host_game.fen());
+
+        guest.send(Message::Move("e7e4".to_string()));
+        let events = poll_until(&mut host, &host_game, |event| {
+            matches!(event, NetworkEvent::Error(_))
+        });
+        assert!(
+            matches!(events.last(), Some(NetworkEvent::Error(error)) if error.starts_with("Impossible move e7e4"))
+        );
+        let events = poll_until(&mut guest, &guest_game, |event| {
+            matches!(event, NetworkEvent::Synced(_))
+        });
+        assert!(events.iter().any(|event| {
+            matches!(event, NetworkEvent::Error(error) if error.starts_with("The opponent reports"))
+        }));
+
+        guest.send(Message::Resign);
+        let events = poll_until(&mut host, &host_game, |event| {
+            matches!(event, NetworkEvent::Resigned(_))
+        });
+        assert!(matches!(
+            events.last(),
+            Some(NetworkEvent::Resigned(PieceColor::Black))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use std::io::{self, BufRead, Read, Write};
+
+/** The largest request body accepted, which is plenty for a FEN or a move. */
+const MAX_BODY_LENGTH: usize = 64 * 1024;
+/** The longest line of a request head accepted, which is plenty for a path or a header. */
+const MAX_LINE_LENGTH: u64 = 8 * 1024;
+/** The most headers of a request accepted. */
+const MAX_HEADER_COUNT: usize = 64;
+
+/** A request of an HTTP/1.1 client. */
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /** The path without the query, e.g. "/games/1". */
+    pub path: String,
+    /** The headers with lowercase names. */
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /** The next request read from a given client. */
+    pub fn read(reader: &mut impl BufRead) -> Result<Request, String> {
+        let request_line = read_line(reader)?;
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(format!("Invalid request line '{}'", request_line));
+        };
+        let path = target.split('?').next().unwrap_or_default().to_string();
+        let mut headers = Vec::new();
+        loop {
+            let line = read_line(reader)?;
+            if line.is_empty() {
+                break;
+            }
+            if headers.len() == MAX_HEADER_COUNT {
+                return Err(format!("More than {} headers", MAX_HEADER_COUNT));
+            }
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid header '{}'", line))?;
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+        let mut request = Request {
+            method: method.to_uppercase(),
+            path,
+            headers,
+            body: String::new(),
+        };
+        let length = match request.header("content-length") {
+            Some(value) => value
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid content length '{}'", value))?,
+            None => 0,
+        };
+        if length > MAX_BODY_LENGTH {
+            return Err(format!("Request body of {} bytes is too large", length));
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).map_err(read_error)?;
+        request.body =
+            String::from_utf8(body).map_err(|_| "Request body is no UTF-8".to_string())?;
+        Ok(request)
+    }
+
+    /** The value of the header with a given lowercase name, if any. */
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/** A response to a request, which closes the connection. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    /** A JSON document, if any. */
+    pub body: Option<String>,
+}
+
+impl Response {
+    /** A response with a given status and JSON body. */
+    pub fn json(status: u16, body: String) -> Self {
+        Self {
+            status,
+            body: Some(body),
+        }
+    }
+    /** A response with a given status and no body. */
+    pub fn empty(status: u16) -> Self {
+        Self { status, body: None }
+    }
+
+    /** Write this response to a given client. Web pages of any origin may use the response. */
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), String> {
+        let mut text = format!(
+            "HTTP/1.1 {} {}\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             Access-Control-Allow-Methods: GET, POST, DELETE, OPTIONS\r\n\
+             Access-Control-Allow-Headers: Content-Type\r\n\
+             Connection: close\r\n",
+            self.status,
+            reason_phrase(self.status)
+        );
+        match &self.body {
+            Some(body) => text.push_str(&format!(
+                "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )),
+            None => text.push_str("Content-Length: 0\r\n\r\n"),
+        }
+        writer
+            .write_all(text.as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|error| error.to_string())
+    }
+}
+
+fn read_line(reader: &mut impl BufRead) -> Result<String, String> {
+    let mut line = String::new();
+    match reader.take(MAX_LINE_LENGTH).read_line(&mut line) {
+        Ok(0) => Err("Connection closed".to_string()),
+        Ok(length) if length as u64 == MAX_LINE_LENGTH && !line.ends_with('\n') => Err(format!(
+            "Line of the request head longer than {} bytes",
+            MAX_LINE_LENGTH
+        )),
+        Ok(_) => Ok(line.trim_end_matches(['\r', '\n']).to_string()),
+        Err(error) => Err(read_error(error)),
+    }
+}
+
+/** The message of a given error while reading a request, which names a timeout as such. */
+fn read_error(error: io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => "Request timed out".to_string(),
+        _ => error.to_string(),
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        101 => "Switching Protocols",
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        _ => "Unknown",
+    }
+}
@@ -0,0 +1,295 @@
+pub mod http;
+pub mod websocket;
+
+use std::collections::BTreeMap;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::domain::game::notation::coordinate_notation;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceColor;
+use crate::server::http::{Request, Response};
+
+/** The TCP port used if none is given. */
+pub const DEFAULT_PORT: u16 = 8080;
+/** The time a client may take to send the next part of its request. */
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/** A game of the server together with the channels of the clients subscribed to its updates. */
+struct ServerGame {
+    game: ChessGame,
+    subscribers: Vec<Sender<String>>,
+}
+
+/** The games of the server keyed by their ID. */
+#[derive(Default)]
+struct Games {
+    next_id: u64,
+    games: BTreeMap<u64, ServerGame>,
+}
+
+/** A server of chess games for clients using HTTP and WebSocket, so they can rely on the rules of
+this crate. Each request is answered on a thread of its own, every response is a JSON document:
+
+- `POST /games` creates a game, optionally from a position: `{"fen": "<FEN>"}`
+- `GET /games` lists the states of all games
+- `GET /games/<ID>` gets the state of a game: FEN, moves, legal moves and status
+- `POST /games/<ID>/moves` executes a move in SAN or coordinate notation: `{"move": "Nf3"}`
+- `DELETE /games/<ID>` deletes a game
+- `GET /games/<ID>/updates` upgrades to a WebSocket, which receives the state of the game now and
+  after each move as a text message
+*/
+#[derive(Default)]
+pub struct GameServer {
+    games: Mutex<Games>,
+}
+
+impl GameServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** Serve the clients of a given listener until it fails. */
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> Result<(), String> {
+        for stream in listener.incoming() {
+            let stream = stream.map_err(|error| error.to_string())?;
+            let server = Arc::clone(&self);
+            thread::spawn(move || server.handle_connection(stream));
+        }
+        Ok(())
+    }
+
+    /** The response to a given request, except for the upgrade to a WebSocket. */
+    pub fn handle(&self, request: &Request) -> Response {
+        let segments: Vec<&str> = request
+            .path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let method = request.method.as_str();
+        match (method, segments.as_slice()) {
+            ("OPTIONS", _) => Response::empty(204),
+            ("GET", ["games"]) => self.list_games(),
+            ("POST", ["games"]) => self.create_game(&request.body),
+            (_, ["games"]) => error(405, "Use GET or POST"),
+            (_, ["games", id, rest @ ..]) => {
+                let Ok(id) = id.parse::<u64>() else {
+                    return error(404, &format!("Unknown game '{}'", id));
+                };
+                match (method, rest) {
+                    ("GET", []) => self.game_state(id),
+                    ("DELETE", []) => self.delete_game(id),
+                    ("POST", ["moves"]) => self.execute_move(id, &request.body),
+                    (_, []) => error(405, "Use GET or DELETE"),
+                    (_, ["moves"]) => error(405, "Use POST"),
+                    (_, ["updates"]) => error(400, "Connect with a WebSocket"),
+                    _ => error(404, &format!("Unknown path '{}'", request.path)),
+                }
+            }
+            _ => error(404, &format!("Unknown path '{}'", request.path)),
+        }
+    }
+
+    /** Answer the requests of a given client. A WebSocket stays open until the client closes it. */
+    fn handle_connection(&self, stream: TcpStream) {
+        if stream.set_read_timeout(Some(REQUEST_TIMEOUT)).is_err() {
+            return;
+        }
+        let Ok(reader) = stream.try_clone() else {
+            return;
+        };
+        let mut reader = BufReader::new(reader);
+        let mut writer = stream;
+        let request = match Request::read(&mut reader) {
+            Ok(request) => request,
+            Err(message) => {
+                let _ = error(400, &message).write_to(&mut writer);
+                return;
+            }
+        };
+        if websocket::is_upgrade(&request) {
+            let updates = match update_path_id(&request.path).and_then(|id| self.subscribe(id)) {
+                Some(updates) => updates,
+                None => {
+                    let _ = error(404, &format!("Unknown path '{}'", request.path))
+                        .write_to(&mut writer);
+                    return;
+                }
+            };
+            if websocket::write_handshake(&request, &mut writer).is_ok() {
+                websocket::serve_updates(writer, reader.buffer().to_vec(), updates);
+            }
+            return;
+        }
+        let _ = self.handle(&request).write_to(&mut writer);
+    }
+
+    fn list_games(&self) -> Response {
+        let games = self.games.lock().unwrap();
+        let states: Vec<Value> = games
+            .games
+            .iter()
+            .map(|(id, server_game)| state(*id, &server_game.game))
+            .collect();
+        Response::json(200, Value::Array(states).to_string())
+    }
+
+    fn create_game(&self, body: &str) -> Response {
+        let game = match member(body, "fen") {
+            Ok(Some(fen)) => match ChessGame::from_fen(&fen) {
+                Ok(game) => game,
+                Err(message) => return error(422, &message),
+            },
+            Ok(None) => ChessGame::new(),
+            Err(message) => return error(400, &message),
+        };
+        let mut games = self.games.lock().unwrap();
+        games.next_id += 1;
+        let id = games.next_id;
+        let response = Response::json(201, state(id, &game).to_string());
+        let subscribers = Vec::new();
+        games.games.insert(id, ServerGame { game, subscribers });
+        response
+    }
+
+    fn game_state(&self, id: u64) -> Response {
+        match self.games.lock().unwrap().games.get(&id) {
+            Some(server_game) => Response::json(200, state(id, &server_game.game).to_string()),
+            None => unknown_game(id),
+        }
+    }
+
+    /** Delete the game with a given ID, which closes the WebSockets of its subscribers. */
+    fn delete_game(&self, id: u64) -> Response {
+        match self.games.lock().unwrap().games.remove(&id) {
+            Some(_) => Response::empty(204),
+            None => unknown_game(id),
+        }
+    }
+
+    /** Execute the move of a given request body in the game with a given ID and send the new state
+    to the subscribers of the game. */
+    fn execute_move(&self, id: u64, body: &str) -> Response {
+        let text = match member(body, "move") {
+            Ok(Some(text)) => text,
+            Ok(None) => return error(400, "Missing member 'move'"),
+            Err(message) => return error(400, &message),
+        };
+        let mut games = self.games.lock().unwrap();
+        let Some(server_game) = games.games.get_mut(&id) else {
+            return unknown_game(id);
+        };
+        let game = &mut server_game.game;
+        if game.is_check_mate() || game.is_stalemate() {
+            return error(409, "The game is over");
+        }
+        let Some((chess_move, promotion)) = game.parse_move(&text) else {
+            return error(422, &format!("Impossible move '{}'", text));
+        };
+        game.execute_move(&chess_move);
+        if let Some(new_type) = promotion {
+            game.exchange_promotion_pawn(new_type);
+        }
+        let new_state = state(id, game).to_string();
+        server_game
+            .subscribers
+            .retain(|subscriber| subscriber.send(new_state.clone()).is_ok());
+        Response::json(200, new_state)
+    }
+
+    /** The updates of the game with a given ID, starting with its current state. None if there is
+    no such game. */
+    fn subscribe(&self, id: u64) -> Option<Receiver<String>> {
+        let mut games = self.games.lock().unwrap();
+        let server_game = games.games.get_mut(&id)?;
+        let (sender, receiver) = channel();
+        sender.send(state(id, &server_game.game).to_string()).ok()?;
+        server_game.subscribers.push(sender);
+        Some(receiver)
+    }
+}
+
+/** The ID of the game of a given path of its updates, e.g. "/games/1/updates". */
+fn update_path_id(path: &str) -> Option<u64> {
+    path.strip_prefix("/games/")?
+        .strip_suffix("/updates")?
+        .parse()
+        .ok()
+}
+
+/** The JSON state of a given game with a given ID. */
+fn state(id: u64, game: &ChessGame) -> Value {
+    let status = match (game.is_check_mate(), game.is_stalemate()) {
+        (true, _) => "checkmate",
+        (_, true) => "stalemate",
+        _ => "ongoing",
+    };
+    let winner = match game.is_check_mate() {
+        true => Some(color_name(game.next_move_color().opponent())),
+        false => None,
+    };
+    let moves: Vec<String> = game
+        .executed_moves()
+        .iter()
+        .map(|executed_move| {
+            let chess_move = Move::new(executed_move.piece, executed_move.target_square);
+            coordinate_notation(&chess_move, executed_move.promotion)
+        })
+        .collect();
+    let san = game.san_line(&game.move_tree().path(game.current_node()));
+    let legal_moves: Vec<String> = game
+        .possible_move_choices()
+        .iter()
+        .map(|(chess_move, promotion)| coordinate_notation(chess_move, *promotion))
+        .collect();
+    json!({
+        "id": id,
+        "fen": game.fen(),
+        "turn": color_name(game.next_move_color()),
+        "status": status,
+        "winner": winner,
+        "check": game.is_check(),
+        "moves": moves,
+        "san": san,
+        "legal_moves": legal_moves,
+    })
+}
+
+fn color_name(color: PieceColor) -> &'static str {
+    match color {
+        PieceColor::White => "white",
+        PieceColor::Black => "black",
+    }
+}
+
+/** The string member with a given name of a given JSON object, if any. An empty text is an empty
+object. */
+fn member(body: &str, name: &str) -> Result<Option<String>, String> {
+    if body.trim().is_empty() {
+        return Ok(None);
+    }
+    let document: Value =
+        serde_json::from_str(body).map_err(|error| format!("Invalid JSON: {}", error))?;
+    let Value::Object(members) = document else {
+        return Err("Expected a JSON object".to_string());
+    };
+    match members.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(format!("Member '{}' is no string", name)),
+    }
+}
+
+fn error(status: u16, message: &str) -> Response {
+    Response::json(status, json!({ "error": message }).to_string())
+}
+
+fn unknown_game(id: u64) -> Response {
+    error(404, &format!("Unknown game {}", id))
+}
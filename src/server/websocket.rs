@@ -0,0 +1,96 @@
+use std::io::{self, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
+
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::{Role, WebSocketConfig};
+use tungstenite::{Error, Message, WebSocket};
+
+use crate::server::http::Request;
+
+/** The largest message accepted from a client. */
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+/** How long to wait for a message of a client before the pending updates are sent. */
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/** Whether a given request asks to upgrade the connection to the WebSocket protocol. */
+pub fn is_upgrade(request: &Request) -> bool {
+    request
+        .header("upgrade")
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
+/** Accept the upgrade of a given request to the WebSocket protocol by writing the response to a
+given client. */
+pub fn write_handshake(request: &Request, writer: &mut impl Write) -> Result<(), String> {
+    let key = request
+        .header("sec-websocket-key")
+        .ok_or_else(|| "Missing WebSocket key".to_string())?;
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    writer
+        .write_all(response.as_bytes())
+        .and_then(|_| writer.flush())
+        .map_err(|error| error.to_string())
+}
+
+/** Send given updates to the client of a WebSocket on a given stream until either side closes it.
+The bytes of the stream already read after the handshake are given. The client may only send pings
+and close frames, which are answered. */
+pub fn serve_updates(stream: TcpStream, read_ahead: Vec<u8>, updates: Receiver<String>) {
+    if stream.set_read_timeout(Some(POLL_INTERVAL)).is_err() {
+        return;
+    }
+    let config = WebSocketConfig {
+        max_message_size: Some(MAX_MESSAGE_SIZE),
+        max_frame_size: Some(MAX_MESSAGE_SIZE),
+        ..WebSocketConfig::default()
+    };
+    let mut socket = WebSocket::from_partially_read(stream, read_ahead, Role::Server, Some(config));
+    let mut is_subscribed = true;
+    loop {
+        if is_subscribed {
+            match send_pending(&mut socket, &updates) {
+                Ok(true) => {}
+                // The channel ends when the game is deleted.
+                Ok(false) => {
+                    is_subscribed = false;
+                    let _ = socket.close(None);
+                }
+                Err(_) => return,
+            }
+        }
+        match socket.read() {
+            Ok(_) => {}
+            Err(Error::Io(error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+/** Send the pending updates to the client of a given WebSocket. Whether more updates may follow,
+which is not the case once the game is deleted. */
+fn send_pending(
+    socket: &mut WebSocket<TcpStream>,
+    updates: &Receiver<String>,
+) -> Result<bool, String> {
+    loop {
+        match updates.try_recv() {
+            Ok(update) => socket
+                .send(Message::Text(update))
+                .map_err(|error| error.to_string())?,
+            Err(TryRecvError::Empty) => return Ok(true),
+            Err(TryRecvError::Disconnected) => return Ok(false),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use crate::domain::game::notation::coordinate_notation;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceType;
+use crate::domain::search::best_move;
+
+/** How long an external engine may take beyond its move time before it forfeits the game. */
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/** A player of the moves of a tournament game. */
+pub enum Engine {
+    /** The search of this crate looking a given number of moves (plies) ahead. */
+    BuiltIn { depth: u32 },
+    /** A program speaking the Universal Chess Interface (UCI) protocol. */
+    Uci(UciEngine),
+}
+
+impl Engine {
+    /** The engine of a given specification: "builtin:<DEPTH>" or "uci:<PATH>", e.g. "builtin:3" or
+    "uci:/usr/bin/stockfish". An external engine thinks a given time in milliseconds per move. */
+    pub fn parse(spec: &str, movetime_ms: u32) -> Result<Engine, String> {
+        match spec.split_once(':') {
+            Some(("builtin", depth)) => depth
+                .parse()
+                .ok()
+                .filter(|depth| *depth > 0)
+                .map(|depth| Engine::BuiltIn { depth })
+                .ok_or_else(|| format!("Invalid search depth '{}'", depth)),
+            Some(("uci", path)) => UciEngine::start(path, movetime_ms).map(Engine::Uci),
+            _ => Err(format!(
+                "Invalid engine '{}', expected builtin:<DEPTH> or uci:<PATH>",
+                spec
+            )),
+        }
+    }
+
+    /** The name of this engine in reports and PGN tags. */
+    pub fn name(&self) -> String {
+        match self {
+            Engine::BuiltIn { depth } => format!("RustyChess depth {}", depth),
+            Engine::Uci(engine) => engine.name.clone(),
+        }
+    }
+
+    /** Prepare this engine for a new game. */
+    pub fn new_game(&mut self) -> Result<(), String> {
+        match self {
+            Engine::BuiltIn { .. } => Ok(()),
+            Engine::Uci(engine) => engine.new_game(),
+        }
+    }
+
+    /** The move this engine chooses in a given game. None if there is no possible move. */
+    pub fn choose_move(
+        &mut self,
+        game: &ChessGame,
+    ) -> Result<Option<(Move, Option<PieceType>)>, String> {
+        match self {
+            Engine::BuiltIn { depth } => Ok(best_move(game, *depth)),
+            Engine::Uci(engine) => engine.choose_move(game),
+        }
+    }
+}
+
+/** An external engine running as a child process, which receives commands on its standard input and
+answers on its standard output. The output is read by a thread of its own. */
+pub struct UciEngine {
+    name: String,
+    movetime_ms: u32,
+    process: Child,
+    input: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl UciEngine {
+    /** Start the engine of a given executable, which thinks a given time in milliseconds per move,
+    and wait until it is ready. */
+    pub fn start(path: &str, movetime_ms: u32) -> Result<UciEngine, String> {
+        let mut process = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| format!("Cannot start engine {}: {}", path, error))?;
+        let (input, output) = match (process.stdin.take(), process.stdout.take()) {
+            (Some(input), Some(output)) => (input, output),
+            _ => return Err(format!("Cannot connect to engine {}", path)),
+        };
+        let (sender, lines) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(output).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut engine = UciEngine {
+            name: path.to_string(),
+            movetime_ms,
+            process,
+            input,
+            lines,
+        };
+        engine.send("uci")?;
+        let id_lines = engine.receive_until("uciok")?;
+        if let Some(name) = id_lines
+            .iter()
+            .find_map(|line| line.strip_prefix("id name "))
+        {
+            engine.name = name.trim().to_string();
+        }
+        Ok(engine)
+    }
+
+    fn new_game(&mut self) -> Result<(), String> {
+        self.send("ucinewgame")?;
+        self.send("isready")?;
+        self.receive_until("readyok").map(|_| ())
+    }
+
+    fn choose_move(
+        &mut self,
+        game: &ChessGame,
+    ) -> Result<Option<(Move, Option<PieceType>)>, String> {
+        let moves: Vec<String> = game
+            .executed_moves()
+            .iter()
+            .map(|executed_move| {
+                let chess_move = Move::new(executed_move.piece, executed_move.target_square);
+                coordinate_notation(&chess_move, executed_move.promotion)
+            })
+            .collect();
+        let mut position = format!("position fen {}", game.initial_position().fen());
+        if !moves.is_empty() {
+            position.push_str(&format!(" moves {}", moves.join(" ")));
+        }
+        self.send(&position)?;
+        self.send(&format!("go movetime {}", self.movetime_ms))?;
+        let lines = self.receive_until("bestmove")?;
+        let best = lines
+            .last()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or_default();
+        match best {
+            "(none)" | "0000" => Ok(None),
+            coordinates => game.parse_move(coordinates).map(Some).ok_or_else(|| {
+                format!("{} plays the impossible move '{}'", self.name, coordinates)
+            }),
+        }
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), String> {
+        writeln!(self.input, "{}", command)
+            .and_then(|_| self.input.flush())
+            .map_err(|error| format!("{} does not respond: {}", self.name, error))
+    }
+
+    /** All lines of the engine up to the one starting with a given keyword. */
+    fn receive_until(&mut self, keyword: &str) -> Result<Vec<String>, String> {
+        let timeout = RESPONSE_TIMEOUT + Duration::from_millis(self.movetime_ms as u64);
+        let mut result = Vec::new();
+        loop {
+            let line = self
+                .lines
+                .recv_timeout(timeout)
+                .map_err(|_| format!("{} does not answer with {}", self.name, keyword))?;
+            let is_answer = line.split_whitespace().next() == Some(keyword);
+            result.push(line);
+            if is_answer {
+                return Ok(result);
+            }
+        }
+    }
+}
+
+impl Drop for UciEngine {
+    /** Ask the engine to quit, and end it if it does not. */
+    fn drop(&mut self) {
+        let _ = writeln!(self.input, "quit").and_then(|_| self.input.flush());
+        thread::sleep(Duration::from_millis(100));
+        if let Ok(None) = self.process.try_wait() {
+            let _ = self.process.kill();
+        }
+        let _ = self.process.wait();
+    }
+}
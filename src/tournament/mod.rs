@@ -0,0 +1,416 @@
+pub mod engine;
+
+use std::collections::HashMap;
+
+use crate::domain::game::ChessGame;
+use crate::domain::pgn::export_pgn_with_tags;
+use crate::domain::pieces::{PieceColor, PieceType};
+use crate::tournament::engine::Engine;
+
+/** The number of consecutive moves (plies) a side has to be ahead in material to win by
+adjudication. */
+const MATERIAL_PLIES: usize = 8;
+/** The quantile of the normal distribution for a confidence of 95%. */
+const CONFIDENCE_95: f64 = 1.959964;
+
+/** Rules which end a game of engines before it is decided by the rules of chess. The draws by the
+fifty-move rule, threefold repetition and insufficient material always apply. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adjudication {
+    /** The number of full moves after which the game is a draw, if any. */
+    pub max_moves: Option<usize>,
+    /** The material advantage in pawns which wins the game if it lasts for several moves, if any. */
+    pub material_threshold: Option<i32>,
+}
+
+impl Default for Adjudication {
+    fn default() -> Self {
+        Self {
+            max_moves: Some(200),
+            material_threshold: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl GameResult {
+    /** The result in Portable Game Notation, e.g. "1-0". */
+    pub fn pgn(&self) -> &'static str {
+        match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        }
+    }
+    fn win_of(color: PieceColor) -> Self {
+        match color {
+            PieceColor::White => GameResult::WhiteWins,
+            PieceColor::Black => GameResult::BlackWins,
+        }
+    }
+}
+
+/** A finished game of a tournament. */
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub game: ChessGame,
+    pub white: String,
+    pub black: String,
+    /** Whether the first engine of the tournament played White. */
+    pub first_is_white: bool,
+    pub result: GameResult,
+    /** Why the game ended, e.g. "checkmate" or "move limit". */
+    pub reason: String,
+}
+
+impl GameRecord {
+    /** The Portable Game Notation of this game as a given round of a tournament. */
+    pub fn pgn(&self, round: usize) -> String {
+        export_pgn_with_tags(
+            &self.game,
+            &[
+                ("Event", "Engine tournament".to_string()),
+                ("Round", round.to_string()),
+                ("White", self.white.clone()),
+                ("Black", self.black.clone()),
+                ("Result", self.result.pgn().to_string()),
+                ("Termination", self.reason.clone()),
+            ],
+        )
+    }
+}
+
+/** A match of two engines over a number of games. The engines alternate colors and play each
+opening twice, once with each color. */
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    pub games: usize,
+    /** The start positions of the games in turn, the standard start position if empty. */
+    pub openings: Vec<ChessGame>,
+    pub adjudication: Adjudication,
+}
+
+impl Tournament {
+    /** Play all games of two given engines. A given function is called after each game with the
+    number of the game and its record. */
+    pub fn play(
+        &self,
+        first: &mut Engine,
+        second: &mut Engine,
+        mut on_game: impl FnMut(usize, &GameRecord),
+    ) -> Vec<GameRecord> {
+        let mut result = Vec::new();
+        for index in 0..self.games {
+            let opening = match self.openings.is_empty() {
+                true => ChessGame::new(),
+                false => self.openings[(index / 2) % self.openings.len()].clone(),
+            };
+            let first_is_white = index % 2 == 0;
+            let (white, black) = match first_is_white {
+                true => (&mut *first, &mut *second),
+                false => (&mut *second, &mut *first),
+            };
+            let record = play_game(white, black, opening, &self.adjudication, first_is_white);
+            on_game(index + 1, &record);
+            result.push(record);
+        }
+        result
+    }
+}
+
+/** Play a game of two given engines from a given position until it is decided or adjudicated. An
+engine which fails to answer or plays an impossible move loses. */
+pub fn play_game(
+    white: &mut Engine,
+    black: &mut Engine,
+    mut game: ChessGame,
+    adjudication: &Adjudication,
+    first_is_white: bool,
+) -> GameRecord {
+    let (white_name, black_name) = (white.name(), black.name());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut advantage_plies = (PieceColor::White, 0);
+    let new_games = white.new_game().map_err(|error| (PieceColor::White, error));
+    let new_games =
+        new_games.and_then(|_| black.new_game().map_err(|error| (PieceColor::Black, error)));
+    let (result, reason) = match new_games {
+        Err((color, error)) => (
+            GameResult::win_of(color.opponent()),
+            format!("forfeit: {}", error),
+        ),
+        Ok(_) => loop {
+            if let Some(end) = game_end(&game, adjudication, &mut positions, &mut advantage_plies) {
+                break end;
+            }
+            let color = game.next_move_color();
+            let engine = match color {
+                PieceColor::White => &mut *white,
+                PieceColor::Black => &mut *black,
+            };
+            match engine.choose_move(&game) {
+                Ok(Some((chess_move, promotion))) => {
+                    game.execute_move(&chess_move);
+                    if let Some(new_type) = promotion {
+                        game.exchange_promotion_pawn(new_type);
+                    }
+                }
+                Ok(None) => {
+                    break (
+                        GameResult::win_of(color.opponent()),
+                        "forfeit: no move".to_string(),
+                    )
+                }
+                Err(error) => {
+                    break (
+                        GameResult::win_of(color.opponent()),
+                        format!("forfeit: {}", error),
+                    )
+                }
+            }
+        },
+    };
+    GameRecord {
+        game,
+        white: white_name,
+        black: black_name,
+        first_is_white,
+        result,
+        reason,
+    }
+}
+
+/** The result of a given game and why, if it is over by the rules or by adjudication. Counts the
+occurrences of its current position and how long a side has been ahead in material. */
+fn game_end(
+    game: &ChessGame,
+    adjudication: &Adjudication,
+    positions: &mut HashMap<String, usize>,
+    advantage_plies: &mut (PieceColor, usize),
+) -> Option<(GameResult, String)> {
+    let color = game.next_move_color();
+    if game.is_check_mate() {
+        return Some((
+            GameResult::win_of(color.opponent()),
+            "checkmate".to_string(),
+        ));
+    }
+    let draw = |reason: &str| Some((GameResult::Draw, reason.to_string()));
+    if game.is_stalemate() {
+        return draw("stalemate");
+    }
+    if game.halfmove_clock() >= 100 {
+        return draw("fifty-move rule");
+    }
+    // The position without the move counters identifies a repetition.
+    let fen = game.fen();
+    let position: Vec<&str> = fen.split_whitespace().take(4).collect();
+    let occurrences = positions.entry(position.join(" ")).or_default();
+    *occurrences += 1;
+    if *occurrences >= 3 {
+        return draw("threefold repetition");
+    }
+    if is_insufficient_material(game) {
+        return draw("insufficient material");
+    }
+    if let Some(threshold) = adjudication.material_threshold {
+        let advantage = game.material_advantage(color);
+        let leader = match advantage {
+            advantage if advantage >= threshold => Some(color),
+            advantage if advantage <= -threshold => Some(color.opponent()),
+            _ => None,
+        };
+        *advantage_plies = match leader {
+            Some(leader) if leader == advantage_plies.0 => (leader, advantage_plies.1 + 1),
+            Some(leader) => (leader, 1),
+            None => (color, 0),
+        };
+        if advantage_plies.1 >= MATERIAL_PLIES {
+            return Some((
+                GameResult::win_of(advantage_plies.0),
+                "material adjudication".to_string(),
+            ));
+        }
+    }
+    let max_moves = adjudication.max_moves?;
+    match game.executed_moves().len() >= max_moves * 2 {
+        true => draw("move limit"),
+        false => None,
+    }
+}
+
+/** Whether neither side can checkmate: Only the kings and at most one bishop or knight are left.
+Other dead positions, e.g. a bishop on each side with both bishops on squares of the same color,
+are not detected and end by the other rules. */
+fn is_insufficient_material(game: &ChessGame) -> bool {
+    let others: Vec<PieceType> = game
+        .pieces
+        .iter()
+        .map(|piece| piece.piece_type)
+        .filter(|piece_type| *piece_type != PieceType::King)
+        .collect();
+    match others.as_slice() {
+        [] => true,
+        [piece_type] => matches!(piece_type, PieceType::Bishop | PieceType::Knight),
+        _ => false,
+    }
+}
+
+/** The start positions of a given text with one position per line in Forsyth-Edwards Notation
+(FEN) or Extended Position Description (EPD). Empty lines and lines starting with # are ignored. */
+pub fn parse_openings(text: &str) -> Result<Vec<ChessGame>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            // The operations of an EPD line follow the first four fields of a FEN.
+            ChessGame::from_fen(line).or_else(|error| {
+                let fields: Vec<&str> = line.split_whitespace().take(4).collect();
+                ChessGame::from_fen(&fields.join(" ")).map_err(|_| error)
+            })
+        })
+        .collect()
+}
+
+/** The wins, losses and draws of the first engine of a tournament. */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Score {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Score {
+    /** The score of the first engine in given games. */
+    pub fn of(records: &[GameRecord]) -> Self {
+        let mut result = Score::default();
+        for record in records {
+            match (record.result, record.first_is_white) {
+                (GameResult::Draw, _) => result.draws += 1,
+                (GameResult::WhiteWins, true) | (GameResult::BlackWins, false) => result.wins += 1,
+                _ => result.losses += 1,
+            }
+        }
+        result
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+    /** The points per game from 0 to 1, counting a draw as half a point. */
+    pub fn ratio(&self) -> f64 {
+        match self.games() {
+            0 => 0.5,
+            games => (self.wins as f64 + self.draws as f64 / 2.0) / games as f64,
+        }
+    }
+
+    /** The difference of the Elo ratings of the engines implied by this score. None if it is
+    infinite, i.e. one engine won all games. */
+    pub fn elo_difference(&self) -> Option<f64> {
+        elo_of_ratio(self.ratio())
+    }
+
+    /** The margin of error of the Elo difference with a confidence of 95%. None if it is infinite,
+    e.g. after too few games. */
+    pub fn elo_margin(&self) -> Option<f64> {
+        let games = self.games() as f64;
+        if games == 0.0 {
+            return None;
+        }
+        let ratio = self.ratio();
+        let variance = (self.wins as f64 * (1.0 - ratio).powi(2)
+            + self.draws as f64 * (0.5 - ratio).powi(2)
+            + self.losses as f64 * ratio.powi(2))
+            / games;
+        let deviation = CONFIDENCE_95 * (variance / games).sqrt();
+        let upper = elo_of_ratio(ratio + deviation)?;
+        let lower = elo_of_ratio(ratio - deviation)?;
+        Some((upper - lower) / 2.0)
+    }
+}
+
+/** The Elo difference for a given expected score from 0 to 1. None if it is infinite. */
+fn elo_of_ratio(ratio: f64) -> Option<f64> {
+    match ratio > 0.0 && ratio < 1.0 {
+        true => Some(-400.0 * (1.0 / ratio - 1.0).log10()),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() < 0.05,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn elo_difference_and_margin_follow_from_the_score() {
+        assert_close(elo_of_ratio(0.75), 190.85);
+        assert_close(elo_of_ratio(0.5), 0.0);
+        assert_close(elo_of_ratio(0.25), -190.85);
+        assert_eq!(elo_of_ratio(1.0), None);
+        assert_eq!(elo_of_ratio(0.0), None);
+        let score = Score {
+            wins: 30,
+            losses: 10,
+            draws: 20,
+        };
+        assert_close(score.elo_difference(), 120.41);
+        assert_close(score.elo_margin(), 75.26);
+        assert_eq!(Score::default().elo_margin(), None);
+        let all_wins = Score {
+            wins: 10,
+            ..Score::default()
+        };
+        assert_eq!(all_wins.elo_difference(), None);
+        assert_eq!(all_wins.elo_margin(), None);
+    }
+
+    #[test]
+    fn positions_without_mating_material_are_drawn() {
+        let game_end_of = |fen: &str| {
+            let game = ChessGame::from_fen(fen).unwrap();
+            game_end(
+                &game,
+                &Adjudication::default(),
+                &mut HashMap::new(),
+                &mut (PieceColor::White, 0),
+            )
+        };
+        for fen in [
+            "8/8/4k3/8/8/4K3/8/8 w - - 0 1",
+            "8/8/4k3/8/8/4K3/8/5B2 w - - 0 1",
+            "8/8/4k3/8/8/4K3/8/6n1 b - - 0 1",
+        ] {
+            assert_eq!(
+                game_end_of(fen),
+                Some((GameResult::Draw, "insufficient material".to_string())),
+                "{}",
+                fen
+            );
+        }
+        for fen in [
+            "8/8/4k3/8/8/4K3/8/5R2 w - - 0 1",
+            "8/8/4k3/8/8/4K3/8/4NN2 w - - 0 1",
+            "8/8/4k3/8/8/4K3/8/4bB2 w - - 0 1",
+            // Both bishops on light squares cannot mate either, but this is not detected.
+            "8/8/4k3/8/2b5/4K3/8/5B2 w - - 0 1",
+        ] {
+            assert_eq!(game_end_of(fen), None, "{}", fen);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use crate::domain::book::OpeningBook;
+use crate::domain::eco::{classify, Opening};
+use crate::domain::evaluation::Evaluation;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceType;
+use crate::domain::search::best_move_unless_cancelled;
+
+type MoveChoice = (Move, Option<PieceType>);
+
+/** A result for a position, identified by its FEN, which is computed in the background. */
+#[derive(Debug)]
+enum Background<T> {
+    Idle,
+    Running {
+        fen: String,
+        receiver: Receiver<T>,
+        /** Set when the position is no longer shown, which may stop the computation early. */
+        cancelled: Arc<AtomicBool>,
+    },
+    Done {
+        fen: String,
+        result: T,
+    },
+}
+
+impl<T: Send + 'static> Background<T> {
+    /** Start computing the result for a given position with a given FEN with a given function,
+    unless it is already known or being computed. Only one computation runs at a time: One for
+    another position is cancelled, and the new one is started by a request after it has stopped.
+    A computation cancelled before it starts is skipped, and the function should stop early once
+    the given flag is set. */
+    fn request(
+        &mut self,
+        fen: &str,
+        game: &ChessGame,
+        compute: impl FnOnce(ChessGame, &AtomicBool) -> T + Send + 'static,
+    ) {
+        match self {
+            Background::Running { fen: running, .. } | Background::Done { fen: running, .. }
+                if *running == fen =>
+            {
+                return
+            }
+            Background::Running { cancelled, .. } => {
+                cancelled.store(true, Ordering::Relaxed);
+                return;
+            }
+            _ => {}
+        }
+        let (sender, receiver) = channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let position = game.clone();
+        let flag = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            if !flag.load(Ordering::Relaxed) {
+                let _ = sender.send(compute(position, &flag));
+            }
+        });
+        *self = Background::Running {
+            fen: fen.to_string(),
+            receiver,
+            cancelled,
+        };
+    }
+
+    /** Take over the result if it is done meanwhile. The result of a cancelled computation is
+    discarded. */
+    fn poll(&mut self) {
+        if let Background::Running {
+            fen,
+            receiver,
+            cancelled,
+        } = self
+        {
+            match receiver.try_recv() {
+                Ok(_) if cancelled.load(Ordering::Relaxed) => *self = Background::Idle,
+                Ok(result) => {
+                    let fen = fen.clone();
+                    *self = Background::Done { fen, result }
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => *self = Background::Idle,
+            }
+        }
+    }
+
+    /** Cancel the running computation, if any, and forget the result. */
+    fn reset(&mut self) {
+        if let Background::Running { cancelled, .. } = self {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        *self = Background::Idle;
+    }
+
+    /** The result for the position of a given FEN, if it is done. */
+    fn result_for(&self, position_fen: &str) -> Option<&T> {
+        match self {
+            Background::Done { fen, result } if fen == position_fen => Some(result),
+            _ => None,
+        }
+    }
+}
+
+/** What an opening book knows about a position. */
+#[derive(Debug, Clone, Default)]
+pub struct BookPosition {
+    /** Whether the move leading to the position is a move of the book. */
+    pub is_book_move: bool,
+    /** The moves of the book in Standard Algebraic Notation with their weights, the highest first. */
+    pub moves: Vec<(String, u16)>,
+}
+
+/** The evaluation of the displayed position and optionally a hint of the best move, both computed
+in the background, so the GUI keeps responding, as well as its opening and the moves of the opening
+book. */
+#[derive(Debug)]
+pub struct Analysis {
+    search_depth: u32,
+    /** The FEN of the position analyzed last, computed once per update. */
+    fen: String,
+    evaluation: Background<Evaluation>,
+    /** The last evaluation which is done, shown until the one of the displayed position is done. */
+    last_evaluation: Option<Evaluation>,
+    hint_enabled: bool,
+    hint: Background<Option<MoveChoice>>,
+    /** The opening of the position of a FEN, if it is recognized. */
+    opening: Option<(String, Option<Opening>)>,
+    /** The moves of the opening book in the position of a FEN, if there is a book. */
+    book_position: Option<(String, BookPosition)>,
+}
+
+impl Analysis {
+    /** A new analysis whose hints search a given number of moves (plies) ahead. */
+    pub fn new(search_depth: u32) -> Self {
+        Self {
+            search_depth,
+            fen: String::new(),
+            evaluation: Background::Idle,
+            last_evaluation: None,
+            hint_enabled: false,
+            hint: Background::Idle,
+            opening: None,
+            book_position: None,
+        }
+    }
+
+    pub fn set_search_depth(&mut self, search_depth: u32) {
+        self.search_depth = search_depth;
+        self.hint.reset();
+    }
+
+    /** Switch the hint on or off. Returns whether it is on now. */
+    pub fn toggle_hint(&mut self) -> bool {
+        self.hint_enabled = !self.hint_enabled;
+        if !self.hint_enabled {
+            self.hint.reset();
+        }
+        self.hint_enabled
+    }
+
+    /** Start analyzing a given displayed position, unless it is analyzed already, and take over
+    the results done meanwhile. The opening and the moves of a given opening book are looked up
+    right away. */
+    pub fn update(&mut self, game: &ChessGame, book: Option<&OpeningBook>) {
+        self.fen = game.fen();
+        let fen = &self.fen;
+        self.evaluation.poll();
+        if let Background::Done { result, .. } = &self.evaluation {
+            self.last_evaluation = Some(*result);
+        }
+        self.evaluation
+            .request(fen, game, |position, _| Evaluation::of(&position));
+        if self.hint_enabled {
+            self.hint.poll();
+            let depth = self.search_depth;
+            self.hint.request(fen, game, move |position, cancelled| {
+                best_move_unless_cancelled(&position, depth, cancelled)
+            });
+        }
+        if self.opening.as_ref().is_none_or(|(known, _)| known != fen) {
+            self.opening = Some((fen.clone(), classify(game)));
+        }
+        let Some(book) = book else {
+            self.book_position = None;
+            return;
+        };
+        if self
+            .book_position
+            .as_ref()
+            .is_some_and(|(known, _)| known == fen)
+        {
+            return;
+        }
+        let moves = book
+            .moves(game)
+            .iter()
+            .map(|book_move| {
+                let san = game.san(&book_move.chess_move, book_move.promotion);
+                (san, book_move.weight)
+            })
+            .collect();
+        let is_book_move = book.is_book_move(game);
+        let position = BookPosition {
+            is_book_move,
+            moves,
+        };
+        self.book_position = Some((fen.clone(), position));
+    }
+
+    /** The opening of the position analyzed last, if it is recognized. */
+    pub fn opening(&self) -> Option<&Opening> {
+        self.opening.as_ref()?.1.as_ref()
+    }
+
+    /** What the opening book knows about the position analyzed last, if there is a book. */
+    pub fn book_position(&self) -> Option<&BookPosition> {
+        self.book_position.as_ref().map(|(_, position)| position)
+    }
+
+    /** The latest evaluation done, which may be the one of a position shown before. */
+    pub fn evaluation(&self) -> Option<&Evaluation> {
+        self.last_evaluation.as_ref()
+    }
+
+    /** The best move of the position analyzed last, if the hint is on and the search is done. */
+    pub fn hint(&self) -> Option<&Move> {
+        if !self.hint_enabled {
+            return None;
+        }
+        self.hint
+            .result_for(&self.fen)?
+            .as_ref()
+            .map(|(chess_move, _)| chess_move)
+    }
+}
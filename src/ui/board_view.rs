@@ -0,0 +1,88 @@
+use crate::domain::chessboard::BoardSquare;
+use crate::domain::game::ChessGame;
+use crate::domain::pieces::PieceColor;
+
+/** The choice of the color shown at the bottom of the board. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Perspective {
+    /** A fixed color is always shown at the bottom. */
+    Fixed(PieceColor),
+    /** The color to move next is shown at the bottom. */
+    SideToMove,
+    /** The color of the only human player is shown at the bottom, e.g. against the computer. White
+    is shown at the bottom if both or no players are human. */
+    HumanSide,
+}
+
+impl Perspective {
+    /** The color shown at the bottom of the board for a given displayed game whose only human
+    player has a given color, if any. */
+    pub fn bottom_color(&self, game: &ChessGame, human_color: Option<PieceColor>) -> PieceColor {
+        match self {
+            Perspective::Fixed(color) => *color,
+            Perspective::SideToMove => game.next_move_color(),
+            Perspective::HumanSide => human_color.unwrap_or(PieceColor::White),
+        }
+    }
+    /** The perspective which shows the board of a given displayed game flipped. */
+    pub fn flipped(&self, game: &ChessGame, human_color: Option<PieceColor>) -> Perspective {
+        Perspective::Fixed(self.bottom_color(game, human_color).opponent())
+    }
+    /** Switch to the next perspective: From a fixed one to the side to move, then to the human
+    side and back to a fixed one. The currently shown side stays at the bottom when switching to a
+    fixed perspective. */
+    pub fn toggled_auto(&self, game: &ChessGame, human_color: Option<PieceColor>) -> Perspective {
+        match self {
+            Perspective::Fixed(_) => Perspective::SideToMove,
+            Perspective::SideToMove => Perspective::HumanSide,
+            Perspective::HumanSide => Perspective::Fixed(self.bottom_color(game, human_color)),
+        }
+    }
+    /** The view of a given displayed game from this perspective. */
+    pub fn view(&self, game: &ChessGame, human_color: Option<PieceColor>) -> BoardView {
+        BoardView::new(self.bottom_color(game, human_color))
+    }
+}
+
+/** Transformation between the intrinsic coordinates of the board, which have rank 8 at the top,
+and the coordinates on the screen. */
+#[derive(Debug, Clone, Copy)]
+pub struct BoardView {
+    flipped: bool,
+}
+
+impl BoardView {
+    /** The view with a given color at the bottom of the screen. */
+    pub fn new(bottom_color: PieceColor) -> Self {
+        Self {
+            flipped: bottom_color == PieceColor::Black,
+        }
+    }
+
+    /** The screen coordinate of a given board coordinate. */
+    pub fn screen_coord(&self, board_coord: (i8, i8)) -> (i8, i8) {
+        match self.flipped {
+            true => (7 - board_coord.0, 7 - board_coord.1),
+            false => board_coord,
+        }
+    }
+    /** The board coordinate of a given screen coordinate. Values outside the board are kept. */
+    pub fn board_coord(&self, screen_coord: (i8, i8)) -> (i8, i8) {
+        match self.flipped && (0..8).contains(&screen_coord.0) && (0..8).contains(&screen_coord.1) {
+            true => (7 - screen_coord.0, 7 - screen_coord.1),
+            false => screen_coord,
+        }
+    }
+    /** The screen position of a given square. */
+    pub fn square_pos(&self, square: &BoardSquare) -> (i32, i32) {
+        let (x, y) = self.screen_coord(square.position());
+        (x as i32, y as i32)
+    }
+    /** The direction on the board of a given direction on the screen. */
+    pub fn board_delta(&self, screen_delta: (i8, i8)) -> (i8, i8) {
+        match self.flipped {
+            true => (-screen_delta.0, -screen_delta.1),
+            false => screen_delta,
+        }
+    }
+}
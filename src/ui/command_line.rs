@@ -0,0 +1,106 @@
+use crate::domain::game::notation::coordinate_notation;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceType;
+use crate::ui::text_input::TextInput;
+
+/** A single possible move the user may type into the [CommandLine]. */
+#[derive(Debug, Clone)]
+pub struct MoveCandidate {
+    pub san: String,
+    pub coordinates: String,
+    pub chess_move: Move,
+    pub promotion: Option<PieceType>,
+}
+
+impl MoveCandidate {
+    /** Whether this candidate's notation starts with a given text. Coordinates are case insensitive. */
+    fn matches(&self, text: &str) -> bool {
+        self.san.starts_with(text) || self.coordinates.starts_with(&text.to_lowercase())
+    }
+}
+
+/** A command line in which the user types a move, either in Standard Algebraic Notation like "Nf3"
+or in coordinate notation like "g1f3". The possible moves matching the typed text are offered for
+completion. */
+#[derive(Debug, Clone)]
+pub struct CommandLine {
+    pub input: TextInput,
+    pub message: Option<String>,
+    candidates: Vec<MoveCandidate>,
+}
+
+impl CommandLine {
+    /** A new empty command line for the possible moves of a given game. */
+    pub fn new(game: &ChessGame) -> Self {
+        let candidates = game
+            .possible_move_choices()
+            .into_iter()
+            .map(|(chess_move, promotion)| MoveCandidate {
+                san: game.san(&chess_move, promotion),
+                coordinates: coordinate_notation(&chess_move, promotion),
+                chess_move,
+                promotion,
+            })
+            .collect();
+        Self {
+            input: TextInput::new(""),
+            message: None,
+            candidates,
+        }
+    }
+
+    /** All candidates matching the text typed so far. */
+    pub fn matches(&self) -> Vec<&MoveCandidate> {
+        let text = self.input.text.trim();
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.matches(text))
+            .collect()
+    }
+
+    /** The only candidate matching the typed text exactly or by its unique beginning. */
+    pub fn chosen_candidate(&self) -> Option<&MoveCandidate> {
+        let text = self.input.text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let matches = self.matches();
+        matches
+            .iter()
+            .find(|candidate| {
+                candidate.san == text
+                    || candidate.san.trim_end_matches(['+', '#']) == text
+                    || candidate.coordinates == text.to_lowercase()
+            })
+            .or(match matches.len() {
+                1 => matches.first(),
+                _ => None,
+            })
+            .copied()
+    }
+
+    /** Complete the typed text to the longest beginning all matching candidates have in common. */
+    pub fn complete(&mut self) {
+        let text = self.input.text.trim().to_string();
+        let notations: Vec<String> = self
+            .matches()
+            .iter()
+            .map(|candidate| match candidate.san.starts_with(&text) {
+                true => candidate.san.clone(),
+                false => candidate.coordinates.clone(),
+            })
+            .collect();
+        if let Some(first) = notations.first() {
+            let mut prefix = first.clone();
+            notations.iter().for_each(|notation| {
+                while !notation.starts_with(&prefix) {
+                    prefix.pop();
+                }
+            });
+            if prefix.len() > text.len() {
+                self.input = TextInput::new(&prefix);
+            }
+        }
+        self.message = None;
+    }
+}
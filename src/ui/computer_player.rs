@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use crate::domain::game::tree::NodeId;
+use crate::domain::game::{ChessGame, Move};
+use crate::domain::pieces::PieceType;
+use crate::domain::search::best_move_unless_cancelled;
+
+type MoveChoice = (Move, Option<PieceType>);
+
+/** A search for the best move running in the background, so the GUI keeps responding. The search
+is cancelled when it is discarded. */
+#[derive(Debug)]
+struct RunningSearch {
+    node: NodeId,
+    receiver: Receiver<Option<MoveChoice>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Drop for RunningSearch {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/** The player which decides on the moves of the computer. */
+#[derive(Debug)]
+pub struct ComputerPlayer {
+    search_depth: u32,
+    search: Option<RunningSearch>,
+}
+
+impl ComputerPlayer {
+    /** A new player which searches a given number of moves (plies) ahead. */
+    pub fn new(search_depth: u32) -> Self {
+        Self {
+            search_depth,
+            search: None,
+        }
+    }
+
+    /** Whether the player is currently searching for a move. */
+    pub fn is_thinking(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /** Start searching for the best move in the current position of a given game, unless the search
+    is already running. */
+    pub fn start(&mut self, game: &ChessGame) {
+        if self.search.is_some() {
+            return;
+        }
+        let (sender, receiver) = channel();
+        let position = game.clone();
+        let depth = self.search_depth;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&cancelled);
+        thread::spawn(move || sender.send(best_move_unless_cancelled(&position, depth, &flag)));
+        self.search = Some(RunningSearch {
+            node: game.current_node(),
+            receiver,
+            cancelled,
+        });
+    }
+
+    /** The move found for the current position of a given game, if the search has finished. A
+    search for any other position is discarded. */
+    pub fn found_move(&mut self, game: &ChessGame) -> Option<MoveChoice> {
+        let search = self.search.as_ref()?;
+        if search.node != game.current_node() {
+            self.search = None;
+            return None;
+        }
+        match search.receiver.try_recv() {
+            Ok(choice) => {
+                self.search = None;
+                choice
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.search = None;
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::config::{PlayerType, Settings};
+use crate::domain::clock::TimeControl;
+use crate::domain::pieces::PieceColor;
+
+/** The time controls offered for a new game, in minutes and increment seconds. */
+const TIME_CONTROLS: [(u32, u32); 7] = [(1, 0), (3, 2), (5, 0), (5, 3), (10, 0), (15, 10), (30, 0)];
+const MAX_SEARCH_DEPTH: u32 = 5;
+
+/** A screen of the menu with its items. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuScreen {
+    Main,
+    NewGame,
+    Settings,
+    InGame,
+    DrawOffer,
+}
+
+/** A single entry of a menu screen which is activated with Enter or a click. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuItem {
+    NewGame,
+    LoadGame,
+    SetupPosition,
+    Settings,
+    Quit,
+    Mode,
+    Color,
+    TimeControl,
+    Variant,
+    Start,
+    Theme,
+    SearchDepth,
+    Back,
+    Resume,
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+}
+
+/** The way a game has ended apart from the rules of chess, i.e. by agreement of the players. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEnd {
+    Resignation(PieceColor),
+    DrawAgreement,
+}
+
+/** The menu shown instead of the move list. Screens opened from another screen return to it. */
+#[derive(Debug, Clone)]
+pub struct Menu {
+    screens: Vec<(MenuScreen, usize)>,
+    pub message: Option<String>,
+}
+
+impl Menu {
+    /** A new menu showing a given screen with its first item selected. */
+    pub fn new(screen: MenuScreen) -> Self {
+        Self {
+            screens: vec![(screen, 0)],
+            message: None,
+        }
+    }
+
+    /** The screen currently shown. */
+    pub fn screen(&self) -> MenuScreen {
+        self.screens.last().unwrap().0
+    }
+    /** The index of the selected item of the current screen. */
+    pub fn selected(&self) -> usize {
+        self.screens.last().unwrap().1
+    }
+    /** The selected item of the current screen. */
+    pub fn selected_item(&self) -> MenuItem {
+        self.items()[self.selected()]
+    }
+
+    /** The items of the current screen. */
+    pub fn items(&self) -> Vec<MenuItem> {
+        match self.screen() {
+            MenuScreen::Main => vec![
+                MenuItem::NewGame,
+                MenuItem::LoadGame,
+                MenuItem::SetupPosition,
+                MenuItem::Settings,
+                MenuItem::Quit,
+            ],
+            MenuScreen::NewGame => vec![
+                MenuItem::Mode,
+                MenuItem::Color,
+                MenuItem::TimeControl,
+                MenuItem::Variant,
+                MenuItem::Start,
+                MenuItem::Back,
+            ],
+            MenuScreen::Settings => vec![MenuItem::Theme, MenuItem::SearchDepth, MenuItem::Back],
+            MenuScreen::InGame => vec![
+                MenuItem::Resume,
+                MenuItem::Resign,
+                MenuItem::OfferDraw,
+                MenuItem::NewGame,
+            ],
+            MenuScreen::DrawOffer => vec![MenuItem::AcceptDraw, MenuItem::DeclineDraw],
+        }
+    }
+
+    /** Select the item with a given index, if there is one. */
+    pub fn select(&mut self, index: usize) {
+        if index < self.items().len() {
+            self.screens.last_mut().unwrap().1 = index;
+        }
+    }
+    /** Select the previous item, wrapping around to the last one. */
+    pub fn select_previous(&mut self) {
+        let count = self.items().len();
+        self.select((self.selected() + count - 1) % count);
+    }
+    /** Select the next item, wrapping around to the first one. */
+    pub fn select_next(&mut self) {
+        let count = self.items().len();
+        self.select((self.selected() + 1) % count);
+    }
+
+    /** Show a given screen which returns to the current one. */
+    pub fn open(&mut self, screen: MenuScreen) {
+        self.screens.push((screen, 0));
+        self.message = None;
+    }
+    /** Return to the screen from which the current one was opened. Returns false if there is no
+    such screen. */
+    pub fn back(&mut self) -> bool {
+        self.message = None;
+        match self.screens.len() > 1 {
+            true => {
+                self.screens.pop();
+                true
+            }
+            false => false,
+        }
+    }
+}
+
+impl MenuScreen {
+    pub fn title(&self) -> &'static str {
+        match self {
+            MenuScreen::Main => "R U S T Y   C H E S S",
+            MenuScreen::NewGame => "NEW GAME",
+            MenuScreen::Settings => "SETTINGS",
+            MenuScreen::InGame => "GAME",
+            MenuScreen::DrawOffer => "DRAW OFFERED",
+        }
+    }
+}
+
+impl MenuItem {
+    /** The text of this item showing the current value of a given setting, if it has one. */
+    pub fn label(&self, settings: &Settings, theme_name: &str) -> String {
+        match self {
+            MenuItem::NewGame => "New Game".to_string(),
+            MenuItem::LoadGame => "Load Game".to_string(),
+            MenuItem::SetupPosition => "Setup Position".to_string(),
+            MenuItem::Settings => "Settings".to_string(),
+            MenuItem::Quit => "Quit".to_string(),
+            MenuItem::Mode => {
+                let mode = match (settings.white, settings.black) {
+                    (PlayerType::Human, PlayerType::Human) => "Human vs Human",
+                    (PlayerType::Computer, PlayerType::Computer) => "Computer vs Computer",
+                    (PlayerType::Remote, _) | (_, PlayerType::Remote) => "Network Game",
+                    _ => "Human vs Computer",
+                };
+                format!("Mode:  {}", mode)
+            }
+            MenuItem::Color => match human_color(settings) {
+                Some(color) => format!("Color: {:?}", color),
+                None => "Color: -".to_string(),
+            },
+            MenuItem::TimeControl => match settings.time_control {
+                Some(time_control) => format!("Time:  {}", time_control),
+                None => "Time:  unlimited".to_string(),
+            },
+            MenuItem::Variant => match settings.fen {
+                Some(_) => "Start: From Position".to_string(),
+                None => "Start: Standard".to_string(),
+            },
+            MenuItem::Start => "Start".to_string(),
+            MenuItem::Theme => format!("Theme: {}", theme_name),
+            MenuItem::SearchDepth => format!("Depth: {}", settings.search_depth),
+            MenuItem::Back => "Back".to_string(),
+            MenuItem::Resume => "Resume".to_string(),
+            MenuItem::Resign => "Resign".to_string(),
+            MenuItem::OfferDraw => "Offer Draw".to_string(),
+            MenuItem::AcceptDraw => "Accept".to_string(),
+            MenuItem::DeclineDraw => "Decline".to_string(),
+        }
+    }
+}
+
+/** The color of the only human player of given settings. None if both or no player are human. */
+pub fn human_color(settings: &Settings) -> Option<PieceColor> {
+    match (settings.white, settings.black) {
+        (PlayerType::Human, PlayerType::Computer | PlayerType::Remote) => Some(PieceColor::White),
+        (PlayerType::Computer | PlayerType::Remote, PlayerType::Human) => Some(PieceColor::Black),
+        _ => None,
+    }
+}
+
+/** Change the players of given settings to the next mode: Human vs Human, Human vs Computer and
+Computer vs Computer. */
+pub fn cycle_mode(settings: &mut Settings) {
+    (settings.white, settings.black) = match (settings.white, settings.black) {
+        (PlayerType::Human, PlayerType::Human) => (PlayerType::Human, PlayerType::Computer),
+        (PlayerType::Computer, PlayerType::Computer) => (PlayerType::Human, PlayerType::Human),
+        _ => (PlayerType::Computer, PlayerType::Computer),
+    };
+}
+
+/** Let the players of given settings swap their colors. */
+pub fn swap_colors(settings: &mut Settings) {
+    (settings.white, settings.black) = (settings.black, settings.white);
+}
+
+/** Change the time control of given settings to the next one offered, after the last one to none. */
+pub fn cycle_time_control(settings: &mut Settings) {
+    let offered: Vec<TimeControl> = TIME_CONTROLS
+        .iter()
+        .map(|(minutes, increment_secs)| TimeControl {
+            initial_secs: minutes * 60,
+            increment_secs: *increment_secs,
+        })
+        .collect();
+    settings.time_control = match settings.time_control {
+        None => offered.first().copied(),
+        Some(current) => match offered.iter().position(|offer| *offer == current) {
+            Some(index) => offered.get(index + 1).copied(),
+            None => offered.first().copied(),
+        },
+    };
+}
+
+/** Change the search depth of given settings to the next one, after the deepest to one. */
+pub fn cycle_search_depth(settings: &mut Settings) {
+    settings.search_depth = settings.search_depth % MAX_SEARCH_DEPTH + 1;
+}
@@ -3,9 +3,20 @@ use bracket_lib::prelude::{main_loop, BError};
 use crate::ui::rendering::create_gui;
 use crate::MainState;
 
+pub mod analysis;
+pub mod board_view;
+pub mod command_line;
+pub mod computer_player;
+pub mod menu;
+pub mod move_list;
 pub mod rendering;
+pub mod replay;
+pub mod status;
+pub mod text_input;
+pub mod theme;
 pub mod user_move;
 
 pub(super) fn main(main_state: MainState) -> BError {
-    main_loop(create_gui(), main_state)
+    let gui = create_gui(&main_state.themes, &main_state.settings.resource_dir);
+    main_loop(gui, main_state)
 }
@@ -0,0 +1,123 @@
+use crate::domain::game::tree::NodeId;
+use crate::domain::game::ChessGame;
+
+/** The number of rows of moves shown at once, each consisting of a move of White and of Black. */
+pub const MOVE_LIST_ROWS: usize = 23;
+
+/** The notation in which the moves of the move list are shown. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveNotation {
+    /** Coordinates of the start and target square, e.g. "G1-F3". */
+    Coordinates,
+    /** Standard Algebraic Notation, e.g. "Nf3". */
+    San,
+}
+
+/** The visible part of the list of moves together with the notation of its moves. The list follows
+the shown move unless it is scrolled by the user. */
+#[derive(Debug)]
+pub struct MoveList {
+    notation: MoveNotation,
+    first_row: usize,
+    followed_ply: Option<usize>,
+    san_keys: Vec<String>,
+    san_notations: Vec<String>,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        Self {
+            notation: MoveNotation::Coordinates,
+            first_row: 0,
+            followed_ply: None,
+            san_keys: Vec::new(),
+            san_notations: Vec::new(),
+        }
+    }
+
+    /** Forget the scroll position and the notations of the moves of a previous game. The chosen
+    notation is kept. */
+    pub fn clear(&mut self) {
+        *self = Self {
+            notation: self.notation,
+            ..Self::new()
+        };
+    }
+
+    /** The index of the first visible row. */
+    pub fn first_row(&self) -> usize {
+        self.first_row
+    }
+    /** Whether there are rows above the visible ones. */
+    pub fn has_rows_above(&self) -> bool {
+        self.first_row > 0
+    }
+    /** Whether there are rows below the visible ones for a given number of moves (plies). */
+    pub fn has_rows_below(&self, move_count: usize) -> bool {
+        self.first_row + MOVE_LIST_ROWS < row_count(move_count)
+    }
+
+    /** Switch between coordinate notation and Standard Algebraic Notation. */
+    pub fn toggle_notation(&mut self) {
+        self.notation = match self.notation {
+            MoveNotation::Coordinates => MoveNotation::San,
+            MoveNotation::San => MoveNotation::Coordinates,
+        }
+    }
+    /** Scroll up by one page. */
+    pub fn page_up(&mut self) {
+        self.first_row = self.first_row.saturating_sub(MOVE_LIST_ROWS);
+    }
+    /** Scroll down by one page, but not beyond the last row for a given number of moves (plies). */
+    pub fn page_down(&mut self, move_count: usize) {
+        let last_first_row = row_count(move_count).saturating_sub(MOVE_LIST_ROWS);
+        self.first_row = (self.first_row + MOVE_LIST_ROWS).min(last_first_row);
+    }
+    /** Scroll to the row of a given shown move (ply) if it is not the one followed so far. Scrolling
+    by the user is therefore kept until another move is shown. */
+    pub fn follow(&mut self, ply: usize) {
+        if self.followed_ply == Some(ply) {
+            return;
+        }
+        self.followed_ply = Some(ply);
+        let row = ply.saturating_sub(1) / 2;
+        if row < self.first_row {
+            self.first_row = row;
+        } else if row >= self.first_row + MOVE_LIST_ROWS {
+            self.first_row = row + 1 - MOVE_LIST_ROWS;
+        }
+    }
+
+    /** The notations of the moves of a given line of nodes of a given game in the chosen notation.
+    The Standard Algebraic Notation is only calculated again if the moves of the line changed. */
+    pub fn notations(&mut self, game: &ChessGame, line: &[NodeId]) -> Vec<String> {
+        let tree = game.move_tree();
+        let coordinates: Vec<String> = line
+            .iter()
+            .filter_map(|node| tree.node(*node).executed_move.as_ref())
+            .map(|executed_move| executed_move.coord_notation())
+            .collect();
+        if self.notation == MoveNotation::Coordinates {
+            return coordinates;
+        }
+        let keys: Vec<String> = line
+            .iter()
+            .filter_map(|node| tree.node(*node).executed_move.as_ref())
+            .zip(coordinates)
+            .map(|(executed_move, notation)| match executed_move.promotion {
+                Some(new_type) => format!("{}{}", notation, new_type.letter()),
+                None => notation,
+            })
+            .collect();
+        if keys != self.san_keys {
+            self.san_notations = game.san_line(line);
+            self.san_keys = keys;
+        }
+        self.san_notations.clone()
+    }
+}
+
+/** The number of rows required for a given number of moves (plies). */
+fn row_count(move_count: usize) -> usize {
+    move_count.div_ceil(2)
+}
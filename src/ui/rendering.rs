@@ -1,8 +1,23 @@
+use std::rc::Rc;
+
 use bracket_lib::prelude::*;
 
 use crate::domain::chessboard::*;
+use crate::domain::clock::ChessClock;
+use crate::domain::eco::Opening;
+use crate::domain::evaluation::Evaluation;
+use crate::domain::game::annotation::{Annotation, MarkerColor};
+use crate::domain::game::tree::NodeId;
 use crate::domain::game::*;
 use crate::domain::pieces::*;
+use crate::ui::analysis::BookPosition;
+use crate::ui::board_view::BoardView;
+use crate::ui::command_line::CommandLine;
+use crate::ui::menu::{GameEnd, Menu};
+use crate::ui::move_list::{MoveList, MOVE_LIST_ROWS};
+use crate::ui::replay::Replay;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::{Theme, ThemeFont, Themes};
 use crate::*;
 
 pub const TILE_WIDTH: i32 = 64;
@@ -19,10 +34,22 @@ const TEXT_LEFT_START: i32 = 8 * FONT_WIDTH_FACTOR + 1;
 const BLOCK_CODE: char = '\u{2588}';
 const POSSIBLE_MOVE_CODE: char = '\u{2591}';
 const CHESS_CODE: char = '\u{2591}';
-const BACKGROUND: (u8, u8, u8) = LIGHT_GREEN;
+const ARROW_CODE: char = '\u{2219}';
+const ARROW_HEAD_CODE: char = '\u{25A0}';
+const SCROLL_UP_CODE: char = '\u{25B2}';
+const SCROLL_DOWN_CODE: char = '\u{25BC}';
+const MOVE_COLUMN_WIDTH: i32 = 10;
+const MOVE_SCROLL_X: i32 = TEXT_LEFT_START + 2 * MOVE_COLUMN_WIDTH;
+const BOOK_X: i32 = MOVE_SCROLL_X + 2;
+const PANEL_WIDTH: i32 = TEXT_WIDTH - TEXT_LEFT_START;
+const COMMENT_ROWS: i32 = 3;
+const COMMENT_TOP: i32 = TEXT_HEIGHT - 2 - COMMENT_ROWS;
+const CAPTURED_TOP: i32 = COMMENT_TOP - 2;
+const CLOCK_TOP: i32 = CAPTURED_TOP - 1;
+const MENU_ITEMS_TOP: i32 = 3;
+const SMALL_PIECES_WIDTH: i32 = TEXT_WIDTH / 2;
 
-const CHESS_PIECES_FILE: &'static str = "chess_pieces.png";
-const TEXT_FILE: &'static str = "terminal8x8.png";
+const EMBEDDED_RESOURCE_DIR: &str = "resources";
 
 const KING_OFFSET: i32 = 0;
 const QUEEN_OFFSET: i32 = KING_OFFSET + 1;
@@ -31,157 +58,267 @@ const BISHOP_OFFSET: i32 = ROOK_OFFSET + 1;
 const KNIGHT_OFFSET: i32 = BISHOP_OFFSET + 1;
 const PAWN_OFFSET: i32 = KNIGHT_OFFSET + 1;
 
-pub fn create_gui() -> BTerm {
-    BTermBuilder::simple(GRAPHICS_WIDTH, GRAPHICS_HEIGHT)
+/** Create the GUI with the fonts of all given themes, whose files are within a given resource
+directory. The consoles initially use the fonts of the selected theme. */
+pub fn create_gui(themes: &Themes, resource_dir: &str) -> BTerm {
+    let theme = themes.selected();
+    let pieces_path = theme.pieces_font.path(resource_dir);
+    // The resource path of the terminal is where it finds its embedded default font. The font
+    // paths of the themes are absolute, so they are not affected.
+    let mut builder = BTermBuilder::simple(GRAPHICS_WIDTH, GRAPHICS_HEIGHT)
         .unwrap()
-        .with_resource_path("resources")
-        .with_font(CHESS_PIECES_FILE, TILE_WIDTH, TILE_HEIGHT)
+        .with_resource_path(EMBEDDED_RESOURCE_DIR);
+    for font in themes.fonts() {
+        builder = builder.with_font(font.path(resource_dir), font.width, font.height);
+    }
+    builder
         .with_fps_cap(25.0)
         .with_title("C H E S S")
         .with_tile_dimensions(TILE_WIDTH, TILE_HEIGHT)
-        .with_sparse_console_no_bg(GRAPHICS_WIDTH, GRAPHICS_HEIGHT, CHESS_PIECES_FILE)
-        .with_simple_console_no_bg(TEXT_WIDTH, TEXT_HEIGHT, TEXT_FILE)
+        .with_sparse_console_no_bg(GRAPHICS_WIDTH, GRAPHICS_HEIGHT, &pieces_path)
+        .with_simple_console_no_bg(TEXT_WIDTH, TEXT_HEIGHT, theme.text_font.path(resource_dir))
+        .with_sparse_console_no_bg(SMALL_PIECES_WIDTH, TEXT_HEIGHT, &pieces_path)
         .with_advanced_input(true)
         .build()
         .unwrap()
 }
 
+/** Switch the consoles to the fonts of the selected one of given themes. */
+fn apply_theme_fonts(themes: &Themes, ctx: &mut BTerm) {
+    let fonts = themes.fonts();
+    // The simple console registers the default font before the fonts of the themes.
+    let font_index = |font: &ThemeFont| 1 + fonts.iter().position(|other| other == font).unwrap();
+    let theme = themes.selected();
+    set_active_console_pieces(ctx);
+    ctx.set_active_font(font_index(&theme.pieces_font), false);
+    set_active_console_small_pieces(ctx);
+    ctx.set_active_font(font_index(&theme.pieces_font), false);
+    set_active_console_texts(ctx);
+    ctx.set_active_font(font_index(&theme.text_font), false);
+}
+
 /** Render a given [Chessboard] onto a given [BTerm]. */
-pub fn render_board(board: &Chessboard, ctx: &mut BTerm) {
+pub fn render_board(board: &Chessboard, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
     set_active_console_board(ctx);
     ctx.cls();
     for square in board.squares() {
+        let (x, y) = view.square_pos(&square);
         ctx.set(
-            square.x(),
-            square.y(),
-            to_square_ui_color(square.color()),
-            BACKGROUND,
+            x,
+            y,
+            theme.square_color(square.color()),
+            theme.background,
             to_cp437(BLOCK_CODE),
         );
     }
 }
 
 /** Render a collection of pieces onto a given [BTerm]. */
-pub fn render_pieces(pieces: &Vec<Piece>, ctx: &mut BTerm) {
+pub fn render_pieces(pieces: &Vec<Piece>, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
     set_active_console_pieces(ctx);
     ctx.cls();
     for piece in pieces {
-        render_piece(&piece, ctx);
+        render_piece(piece, view, theme, ctx);
     }
 }
 
 /** Render a single given [Piece] onto a given [BTerm]. */
-pub fn render_piece(piece: &Piece, ctx: &mut BTerm) {
+pub fn render_piece(piece: &Piece, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
     set_active_console_pieces(ctx);
+    let (x, y) = view.square_pos(&piece.square);
     ctx.set(
-        piece.square.x(),
-        piece.square.y(),
-        to_piece_ui_color(piece.color),
-        to_square_ui_color(piece.square.color()),
-        match piece.piece_type {
-            PieceType::King => KING_OFFSET,
-            PieceType::Queen => QUEEN_OFFSET,
-            PieceType::Rook => ROOK_OFFSET,
-            PieceType::Bishop => BISHOP_OFFSET,
-            PieceType::Knight => KNIGHT_OFFSET,
-            PieceType::Pawn => PAWN_OFFSET,
-        },
+        x,
+        y,
+        theme.piece_color(piece.color),
+        theme.square_color(piece.square.color()),
+        piece_offset(piece.piece_type),
     );
 }
 
-pub fn set_active_console_pieces(ctx: &mut BTerm) {
-    ctx.set_active_console(1);
+/** The offset of a given piece type's glyph within the chess pieces font. */
+fn piece_offset(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::King => KING_OFFSET,
+        PieceType::Queen => QUEEN_OFFSET,
+        PieceType::Rook => ROOK_OFFSET,
+        PieceType::Bishop => BISHOP_OFFSET,
+        PieceType::Knight => KNIGHT_OFFSET,
+        PieceType::Pawn => PAWN_OFFSET,
+    }
 }
 
-fn set_active_console_board(ctx: &mut BTerm) {
-    ctx.set_active_console(0);
+/** Render a given piece which is dragged by the user at a given coordinate of the mouse. */
+pub fn render_dragged_piece(piece: &Piece, mouse_point: Point, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_pieces(ctx);
+    ctx.set(
+        mouse_point.x,
+        mouse_point.y,
+        theme.piece_color(piece.color),
+        theme.background,
+        piece_offset(piece.piece_type),
+    );
 }
 
-fn to_square_ui_color(piece_color: SquareColor) -> (u8, u8, u8) {
-    return match piece_color {
-        SquareColor::White => LIGHT_GRAY,
-        SquareColor::Black => DARK_GRAY,
-    };
+pub fn set_active_console_pieces(ctx: &mut BTerm) {
+    ctx.set_active_console(1);
 }
 
-fn to_piece_ui_color(piece_color: PieceColor) -> (u8, u8, u8) {
-    return match piece_color {
-        PieceColor::White => WHITE,
-        PieceColor::Black => BLACK,
-    };
+fn set_active_console_board(ctx: &mut BTerm) {
+    ctx.set_active_console(0);
 }
 
-pub fn render_possible_moves(possible_moves: Vec<Move>, ctx: &mut BTerm) {
+pub fn render_possible_moves(
+    possible_moves: Vec<Move>,
+    view: &BoardView,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
     set_active_console_board(ctx);
     possible_moves.iter().for_each(|possible_move| {
         let target_square = possible_move.target;
+        let (x, y) = view.square_pos(&target_square);
         ctx.set(
-            target_square.x(),
-            target_square.y(),
-            LIGHT_GREEN,
-            to_square_ui_color(target_square.color()),
+            x,
+            y,
+            theme.possible_move,
+            theme.square_color(target_square.color()),
             to_cp437(POSSIBLE_MOVE_CODE),
         );
     });
 }
 
-pub fn render_game_end_and_check(game: &ChessGame, ctx: &mut BTerm) {
+/** Render the start and target squares of the last executed move of a given game. */
+pub fn render_last_move(game: &ChessGame, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
+    if let Some(last_move) = game.executed_moves().last() {
+        set_active_console_board(ctx);
+        [last_move.start_square, last_move.target_square]
+            .iter()
+            .for_each(|square| {
+                let (x, y) = view.square_pos(square);
+                ctx.set(
+                    x,
+                    y,
+                    theme.last_move,
+                    theme.square_color(square.color()),
+                    to_cp437(CHESS_CODE),
+                );
+            });
+    }
+}
+
+/** Render the rank labels along the left edge and the file labels along the bottom edge of the
+board. */
+pub fn render_coordinates(board: &Chessboard, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    for screen_index in 0..8 {
+        if let Some(square) = board.square_at(view.board_coord((0, screen_index))) {
+            ctx.print_color(
+                0,
+                screen_index as i32 * FONT_HEIGHT_FACTOR,
+                theme.label_color(square.color()),
+                theme.background,
+                square.rank(),
+            );
+        }
+        if let Some(square) = board.square_at(view.board_coord((screen_index, 7))) {
+            ctx.print_color(
+                (screen_index as i32 + 1) * FONT_WIDTH_FACTOR - 1,
+                8 * FONT_HEIGHT_FACTOR - 1,
+                theme.label_color(square.color()),
+                theme.background,
+                square.file(),
+            );
+        }
+    }
+}
+
+pub fn render_game_end_and_check(
+    game: &ChessGame,
+    view: &BoardView,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
     if game.is_check_mate() {
-        render_check_mate(ctx);
+        render_check_mate(theme, ctx);
     } else if game.is_stalemate() {
-        render_stalemate(ctx);
+        render_stalemate(theme, ctx);
     } else {
-        render_check(game, ctx);
+        render_check(game, view, theme, ctx);
     }
 }
 
-fn render_check(game: &ChessGame, ctx: &mut BTerm) {
+fn render_check(game: &ChessGame, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
     let chess_moves = game.chess_moves();
     if !chess_moves.is_empty() {
         set_active_console_board(ctx);
         let chess_square = chess_moves.iter().nth(0).unwrap().target;
+        let (x, y) = view.square_pos(&chess_square);
         ctx.set(
-            chess_square.x(),
-            chess_square.y(),
-            RED,
-            to_square_ui_color(chess_square.color()),
+            x,
+            y,
+            theme.check,
+            theme.square_color(chess_square.color()),
             to_cp437(CHESS_CODE),
         );
     }
 }
 
-fn render_check_mate(ctx: &mut BTerm) {
+fn render_check_mate(theme: &Theme, ctx: &mut BTerm) {
     set_active_console_texts(ctx);
     ctx.cls();
-    ctx.print(TEXT_LEFT_START, 0, "C H E C K   M A T E");
+    ctx.print_color(
+        TEXT_LEFT_START,
+        0,
+        theme.text,
+        theme.background,
+        "C H E C K   M A T E",
+    );
 }
 
-fn render_stalemate(ctx: &mut BTerm) {
+fn render_stalemate(theme: &Theme, ctx: &mut BTerm) {
     set_active_console_texts(ctx);
     ctx.cls();
-    ctx.print(TEXT_LEFT_START, 0, "STALEMATE");
+    ctx.print_color(
+        TEXT_LEFT_START,
+        0,
+        theme.text,
+        theme.background,
+        "STALEMATE",
+    );
 }
 
-pub(super) fn render_promotion_pawn(optional_pawn: Option<Piece>, ctx: &mut BTerm) {
+pub(super) fn render_promotion_pawn(
+    optional_pawn: Option<Piece>,
+    view: &BoardView,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
     if let Some(pawn) = optional_pawn {
         set_active_console_board(ctx);
+        let (x, y) = view.square_pos(&pawn.square);
         ctx.set(
-            pawn.square.x(),
-            pawn.square.y(),
-            LIGHT_SALMON,
-            to_square_ui_color(pawn.square.color()),
+            x,
+            y,
+            theme.promotion,
+            theme.square_color(pawn.square.color()),
             to_cp437(CHESS_CODE),
         );
     }
 }
 
-pub(super) fn render_selected_piece(piece: &Piece, ctx: &mut BTerm) {
+pub(super) fn render_selected_piece(
+    piece: &Piece,
+    view: &BoardView,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
     set_active_console_board(ctx);
+    let (x, y) = view.square_pos(&piece.square);
     ctx.set(
-        piece.square.x(),
-        piece.square.y(),
-        LIGHT_CYAN,
-        to_square_ui_color(piece.square.color()),
+        x,
+        y,
+        theme.selection,
+        theme.square_color(piece.square.color()),
         to_cp437(CHESS_CODE),
     );
 }
@@ -190,48 +327,867 @@ fn set_active_console_texts(ctx: &mut BTerm) {
     ctx.set_active_console(2)
 }
 
-pub fn render_executed_moves(game: &ChessGame, ctx: &mut BTerm) {
+fn set_active_console_small_pieces(ctx: &mut BTerm) {
+    ctx.set_active_console(3)
+}
+
+/** Render the pieces captured by each side together with the material advantage of the side which
+is ahead. The side shown at the bottom of the board is rendered in the lower row. */
+pub fn render_captured_pieces(
+    game: &ChessGame,
+    bottom_color: PieceColor,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
+    set_active_console_small_pieces(ctx);
+    ctx.cls();
+    for (row, color) in [bottom_color.opponent(), bottom_color].iter().enumerate() {
+        let y = CAPTURED_TOP + row as i32;
+        let advantage = game.material_advantage(*color);
+        if advantage > 0 {
+            set_active_console_texts(ctx);
+            ctx.print_color(
+                TEXT_LEFT_START,
+                y,
+                theme.text,
+                theme.background,
+                format!("+{}", advantage),
+            );
+        }
+        let mut captured = game.captured_pieces(color.opponent());
+        captured.sort_by_key(|piece| -piece.piece_type.value());
+        set_active_console_small_pieces(ctx);
+        let first_x = (TEXT_LEFT_START + 4) / 2;
+        for (index, piece) in captured
+            .iter()
+            .take((SMALL_PIECES_WIDTH - first_x) as usize)
+            .enumerate()
+        {
+            ctx.set(
+                first_x + index as i32,
+                y,
+                theme.piece_color(piece.color),
+                theme.background,
+                piece_offset(piece.piece_type),
+            );
+        }
+    }
+}
+
+/** Render the visible part of the executed moves of a given game followed by their continuation in
+the move tree. The move leading to the shown position is highlighted, which is either the move of
+a given number of moves (plies) of the replay or the last executed move. A game starting with Black
+to move leaves the first White column empty. */
+pub fn render_executed_moves(
+    game: &ChessGame,
+    move_list: &mut MoveList,
+    replay_ply: Option<usize>,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
     set_active_console_texts(ctx);
-    let mut row = 1;
-    game.executed_moves().iter().for_each(|executed_move| {
-        let move_number = (row - 1) / 2 + 1;
-        let column_offset = 1 - row % 2;
+    let tree = game.move_tree();
+    let executed_count = game.executed_moves().len();
+    let shown_ply = replay_ply.unwrap_or(executed_count);
+    let line = displayed_line(game);
+    let notations = move_list.notations(game, &line);
+    let ply_offset = game.initial_ply_offset();
+    let first_slot = move_list.first_row() * 2;
+    let moves = line.iter().filter_map(|node| {
+        let move_node = tree.node(*node);
+        move_node.executed_move.as_ref().map(|_| move_node)
+    });
+    for (index, (move_node, notation)) in moves.zip(notations).enumerate() {
+        let ply = index + 1;
+        let slot = index + ply_offset;
+        if !(first_slot..first_slot + 2 * MOVE_LIST_ROWS).contains(&slot) {
+            continue;
+        }
+        let notation = format!("{}{}", notation, move_node.annotation.nag_symbols());
+        let move_number = game.initial_fullmove_number() + slot / 2;
+        let column_offset = (slot % 2) as i32;
+        let row = ((slot - first_slot) / 2 + 1) as i32;
+        if index == 0 && column_offset == 1 {
+            ctx.print_color(
+                TEXT_LEFT_START,
+                row,
+                theme.text,
+                theme.background,
+                format!("{}. ...", move_number),
+            );
+        }
         let string = match column_offset == 0 {
-            true => format!("{}. {}", move_number, executed_move.coord_notation()),
-            false => executed_move.coord_notation(),
+            true => format!("{}. {}", move_number, notation),
+            false => notation,
         };
-        ctx.print(TEXT_LEFT_START + column_offset * 10, move_number, string);
-        row += 1;
+        let color = if ply == shown_ply && replay_ply.is_some() {
+            theme.replay_move
+        } else if ply == shown_ply {
+            theme.last_move
+        } else if ply > executed_count {
+            theme.secondary_text
+        } else {
+            theme.text
+        };
+        ctx.print_color(
+            TEXT_LEFT_START + column_offset * MOVE_COLUMN_WIDTH,
+            row,
+            color,
+            theme.background,
+            string,
+        );
+    }
+    if move_list.has_rows_above() {
+        ctx.set(
+            MOVE_SCROLL_X,
+            1,
+            theme.text,
+            theme.background,
+            to_cp437(SCROLL_UP_CODE),
+        );
+    }
+    if move_list.has_rows_below(line.len() + ply_offset) {
+        ctx.set(
+            MOVE_SCROLL_X,
+            MOVE_LIST_ROWS as i32,
+            theme.text,
+            theme.background,
+            to_cp437(SCROLL_DOWN_CODE),
+        );
+    }
+}
+
+/** Render beside the move list whether the displayed move is a move of the opening book and the
+moves of the book in the displayed position with their share of the weights in percent. */
+fn render_book_position(book_position: &BookPosition, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    if book_position.is_book_move {
+        ctx.print_color(BOOK_X, 1, theme.last_move, theme.background, "Book move");
+    }
+    if book_position.moves.is_empty() {
+        return;
+    }
+    ctx.print_color(BOOK_X, 2, theme.secondary_text, theme.background, "BOOK");
+    let total: u32 = book_position
+        .moves
+        .iter()
+        .map(|(_, weight)| *weight as u32)
+        .sum();
+    let rows = book_position.moves.iter().take(MOVE_LIST_ROWS - 2);
+    for (row, (san, weight)) in rows.enumerate() {
+        let percent = match total {
+            0 => 0,
+            total => (*weight as u32 * 100 + total / 2) / total,
+        };
+        let line = format!("{:<5}{:>3}%", san, percent);
+        ctx.print_color(BOOK_X, row as i32 + 3, theme.text, theme.background, line);
+    }
+}
+
+/** Render the terms of a given evaluation in place of the move list, in pawns from White's point of
+view. */
+fn render_evaluation_terms(evaluation: Option<&Evaluation>, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    ctx.print_color(
+        TEXT_LEFT_START,
+        1,
+        theme.secondary_text,
+        theme.background,
+        "EVALUATION",
+    );
+    let Some(evaluation) = evaluation else {
+        ctx.print_color(
+            TEXT_LEFT_START,
+            3,
+            theme.text,
+            theme.background,
+            "Not done yet",
+        );
+        return;
+    };
+    for (row, line) in evaluation.to_string().lines().enumerate() {
+        ctx.print_color(
+            TEXT_LEFT_START,
+            row as i32 + 3,
+            theme.text,
+            theme.background,
+            line,
+        );
+    }
+}
+
+/** The nodes of the executed moves of a given game followed by the main line continuation of its
+current node. */
+pub fn displayed_line(game: &ChessGame) -> Vec<NodeId> {
+    let tree = game.move_tree();
+    let mut result = tree.path(game.current_node());
+    result.append(&mut tree.continuation(game.current_node()));
+    result
+}
+
+/** Render the comment of a given annotation below the move list. */
+pub fn render_comment(annotation: &Annotation, theme: &Theme, ctx: &mut BTerm) {
+    render_comment_lines(&annotation.comment, theme.text, theme, ctx);
+}
+
+/** Render a comment which is currently edited by the user. */
+pub(super) fn render_comment_input(input: &TextInput, theme: &Theme, ctx: &mut BTerm) {
+    render_comment_lines(&format!("{}_", input.text), theme.input_text, theme, ctx);
+}
+
+fn render_comment_lines(text: &str, color: (u8, u8, u8), theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    let lines = wrap_text(text, PANEL_WIDTH);
+    let skipped = lines.len().saturating_sub(COMMENT_ROWS as usize);
+    lines
+        .iter()
+        .skip(skipped)
+        .enumerate()
+        .for_each(|(index, line)| {
+            ctx.print_color(
+                TEXT_LEFT_START,
+                COMMENT_TOP + index as i32,
+                color,
+                theme.background,
+                line,
+            );
+        });
+}
+
+/** Split a given text into lines of a given maximum width, breaking at spaces where possible. */
+fn wrap_text(text: &str, width: i32) -> Vec<String> {
+    let width = width as usize;
+    let mut result: Vec<String> = Vec::new();
+    let mut line = String::new();
+    text.split(' ').for_each(|word| {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            result.push(line.clone());
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+        while line.len() > width {
+            let rest = line.split_off(width);
+            result.push(line.clone());
+            line = rest;
+        }
+    });
+    if !line.is_empty() {
+        result.push(line);
+    }
+    result
+}
+
+/** Render the highlighted squares and the arrows of a given annotation onto the board. */
+pub fn render_markers(annotation: &Annotation, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_board(ctx);
+    annotation.squares.iter().for_each(|marker| {
+        let (x, y) = view.square_pos(&marker.square);
+        ctx.set(
+            x,
+            y,
+            to_marker_ui_color(marker.color),
+            theme.square_color(marker.square.color()),
+            to_cp437(CHESS_CODE),
+        );
+    });
+    set_active_console_texts(ctx);
+    annotation.arrows.iter().for_each(|arrow| {
+        let color = to_marker_ui_color(arrow.color);
+        let from = square_text_center(arrow.from, view);
+        let to = square_text_center(arrow.to, view);
+        line2d_bresenham(from, to).iter().for_each(|point| {
+            ctx.set(
+                point.x,
+                point.y,
+                color,
+                theme.background,
+                to_cp437(ARROW_CODE),
+            );
+        });
+        ctx.set(
+            to.x,
+            to.y,
+            color,
+            theme.background,
+            to_cp437(ARROW_HEAD_CODE),
+        );
+    });
+}
+
+/** Render a given best move as a hint: its start and target squares highlighted like possible moves,
+connected by an arrow. */
+pub fn render_hint(hint: &Move, view: &BoardView, theme: &Theme, ctx: &mut BTerm) {
+    let start = Move::new(hint.piece, hint.piece.square);
+    render_possible_moves(vec![start, hint.clone()], view, theme, ctx);
+    set_active_console_texts(ctx);
+    let from = square_text_center(hint.piece.square, view);
+    let to = square_text_center(hint.target, view);
+    line2d_bresenham(from, to).iter().for_each(|point| {
+        ctx.set(
+            point.x,
+            point.y,
+            theme.possible_move,
+            theme.background,
+            to_cp437(ARROW_CODE),
+        );
+    });
+    ctx.set(
+        to.x,
+        to.y,
+        theme.possible_move,
+        theme.background,
+        to_cp437(ARROW_HEAD_CODE),
+    );
+}
+
+/** Render a bar beside the board showing a given evaluation in centipawns from White's point of
+view. The share of each color is its expected score, its end of the bar next to its side. */
+pub fn render_evaluation_bar(
+    centipawns: i32,
+    bottom_color: PieceColor,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
+    set_active_console_texts(ctx);
+    let white_share = 1.0 / (1.0 + 10f32.powf(-centipawns as f32 / 400.0));
+    let white_rows = (white_share * TEXT_HEIGHT as f32).round() as i32;
+    (0..TEXT_HEIGHT).for_each(|y| {
+        let rows_from_white = match bottom_color {
+            PieceColor::White => TEXT_HEIGHT - 1 - y,
+            PieceColor::Black => y,
+        };
+        let color = match rows_from_white < white_rows {
+            true => PieceColor::White,
+            false => PieceColor::Black,
+        };
+        ctx.set(
+            TEXT_LEFT_START - 1,
+            y,
+            theme.piece_color(color),
+            theme.background,
+            to_cp437(BLOCK_CODE),
+        );
     });
 }
 
+/** The coordinate of the text console in the center of a given square. */
+fn square_text_center(square: BoardSquare, view: &BoardView) -> Point {
+    let (x, y) = view.square_pos(&square);
+    Point::new(
+        x * FONT_WIDTH_FACTOR + FONT_WIDTH_FACTOR / 2,
+        y * FONT_HEIGHT_FACTOR + FONT_HEIGHT_FACTOR / 2,
+    )
+}
+
+fn to_marker_ui_color(marker_color: MarkerColor) -> (u8, u8, u8) {
+    match marker_color {
+        MarkerColor::Green => GREEN,
+        MarkerColor::Red => RED,
+        MarkerColor::Yellow => YELLOW,
+        MarkerColor::Blue => BLUE,
+    }
+}
+
+/** Render all the alternatives of the move leading to a given node of the move tree, if there are
+any. The move of the given node is highlighted. */
+pub fn render_variations(game: &ChessGame, node: NodeId, theme: &Theme, ctx: &mut BTerm) {
+    let tree = game.move_tree();
+    let siblings = tree.siblings(node);
+    if siblings.len() < 2 {
+        return;
+    }
+    set_active_console_texts(ctx);
+    ctx.print_color(
+        TEXT_LEFT_START,
+        TEXT_HEIGHT - 2,
+        theme.text,
+        theme.background,
+        "VAR",
+    );
+    let mut x = TEXT_LEFT_START + 4;
+    siblings.iter().for_each(|sibling| {
+        if let Some(executed_move) = &tree.node(*sibling).executed_move {
+            let notation = executed_move.coord_notation();
+            let color = match *sibling == node {
+                true => theme.replay_move,
+                false => theme.text,
+            };
+            ctx.print_color(x, TEXT_HEIGHT - 2, color, theme.background, &notation);
+            x += notation.len() as i32 + 1;
+        }
+    });
+}
+
+/** The target of a click into the move list. */
+pub enum MoveListClick {
+    /** The move after the given number of moves (plies). */
+    Move(usize),
+    PageUp,
+    PageDown,
+}
+
+/** The target of a click at a given text coordinate into the move list showing a given number of
+moves (plies), whose first move is shown at a given offset. None if there is nothing to click at
+that coordinate.
+See: [render_executed_moves] */
+pub fn move_list_click_at(
+    text_coord: (i32, i32),
+    move_list: &MoveList,
+    move_count: usize,
+    ply_offset: usize,
+) -> Option<MoveListClick> {
+    let (x, y) = text_coord;
+    let column = x - TEXT_LEFT_START;
+    if !(1..=MOVE_LIST_ROWS as i32).contains(&y) {
+        return None;
+    }
+    if x == MOVE_SCROLL_X {
+        return match y {
+            1 if move_list.has_rows_above() => Some(MoveListClick::PageUp),
+            y if y == MOVE_LIST_ROWS as i32
+                && move_list.has_rows_below(move_count + ply_offset) =>
+            {
+                Some(MoveListClick::PageDown)
+            }
+            _ => None,
+        };
+    }
+    if !(0..2 * MOVE_COLUMN_WIDTH).contains(&column) {
+        return None;
+    }
+    let row = move_list.first_row() + (y - 1) as usize;
+    let slot = row * 2 + (column / MOVE_COLUMN_WIDTH) as usize;
+    match (ply_offset..move_count + ply_offset).contains(&slot) {
+        true => Some(MoveListClick::Move(slot - ply_offset + 1)),
+        false => None,
+    }
+}
+
+/** Render the remaining time of both players of a given chess clock, the one of a given color to
+move highlighted. */
+fn render_clock(clock: &ChessClock, color_to_move: PieceColor, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    for (column, color) in [PieceColor::White, PieceColor::Black].iter().enumerate() {
+        let text_color = match *color == color_to_move {
+            true => theme.last_move,
+            false => theme.text,
+        };
+        ctx.print_color(
+            TEXT_LEFT_START + column as i32 * MOVE_COLUMN_WIDTH,
+            CLOCK_TOP,
+            text_color,
+            theme.background,
+            format_clock_time(clock.remaining_ms(*color)),
+        );
+    }
+    if let Some(color) = clock.flagged() {
+        ctx.print_color(
+            TEXT_LEFT_START,
+            0,
+            theme.check,
+            theme.background,
+            format!("TIME OUT - {:?} wins", color.opponent()),
+        );
+    }
+}
+
+/** A given time in milliseconds as minutes and seconds, with tenths of seconds below ten seconds. */
+fn format_clock_time(time_ms: f32) -> String {
+    let tenths = (time_ms / 100.0).ceil() as u32;
+    match tenths < 100 {
+        true => format!("0:{:02}.{}", tenths / 10, tenths % 10),
+        false => {
+            let secs = tenths.div_ceil(10);
+            format!("{}:{:02}", secs / 60, secs % 60)
+        }
+    }
+}
+
+fn render_thinking(theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    ctx.print_color(
+        TEXT_LEFT_START,
+        0,
+        theme.secondary_text,
+        theme.background,
+        "Thinking...",
+    );
+}
+
+/** Render the result of the last action of the user, shortened to the width of the panel. */
+fn render_status_message(message: &str, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    let text: String = message.chars().take(PANEL_WIDTH as usize).collect();
+    ctx.print_color(TEXT_LEFT_START, 0, theme.input_text, theme.background, text);
+}
+
+/** Render what happened last in a network game, shortened to the width of the panel. */
+fn render_network_message(message: &str, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    let text: String = message.chars().take(PANEL_WIDTH as usize).collect();
+    ctx.print_color(
+        TEXT_LEFT_START,
+        0,
+        theme.secondary_text,
+        theme.background,
+        text,
+    );
+}
+
+/** Render the opening of the displayed position as a header, shortened to the width of the panel. */
+fn render_opening(opening: &Opening, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    let text: String = opening
+        .to_string()
+        .chars()
+        .take(PANEL_WIDTH as usize)
+        .collect();
+    ctx.print_color(TEXT_LEFT_START, 0, theme.text, theme.background, text);
+}
+
+/** Render the end of a game by resignation or agreement. */
+fn render_game_end(game_end: GameEnd, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    let text = match game_end {
+        GameEnd::Resignation(color) => format!("{:?} RESIGNS", color).to_uppercase(),
+        GameEnd::DrawAgreement => "DRAW AGREED".to_string(),
+    };
+    ctx.print_color(TEXT_LEFT_START, 0, theme.text, theme.background, text);
+}
+
+/** Render the title and the items of the current screen of a given menu, the selected one
+highlighted, followed by its message. The items show the values of given settings. */
+fn render_menu(menu: &Menu, settings: &Settings, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    ctx.print_color(
+        TEXT_LEFT_START,
+        1,
+        theme.text,
+        theme.background,
+        menu.screen().title(),
+    );
+    let items = menu.items();
+    for (index, item) in items.iter().enumerate() {
+        let label = item.label(settings, &theme.name);
+        let (prefix, color) = match index == menu.selected() {
+            true => ("> ", theme.last_move),
+            false => ("  ", theme.text),
+        };
+        ctx.print_color(
+            TEXT_LEFT_START,
+            MENU_ITEMS_TOP + index as i32,
+            color,
+            theme.background,
+            format!("{}{}", prefix, label),
+        );
+    }
+    if let Some(message) = &menu.message {
+        let top = MENU_ITEMS_TOP + items.len() as i32 + 1;
+        for (index, line) in wrap_text(message, PANEL_WIDTH).iter().enumerate() {
+            ctx.print_color(
+                TEXT_LEFT_START,
+                top + index as i32,
+                theme.check,
+                theme.background,
+                line,
+            );
+        }
+    }
+}
+
+/** The index of the menu item at a given text coordinate. None if there is no item.
+See: [render_menu] */
+pub fn menu_click_at(text_coord: (i32, i32), menu: &Menu) -> Option<usize> {
+    let (x, y) = text_coord;
+    let index = y - MENU_ITEMS_TOP;
+    match x >= TEXT_LEFT_START && (0..menu.items().len() as i32).contains(&index) {
+        true => Some(index as usize),
+        false => None,
+    }
+}
+
+/** Render the FEN of a position entered by the user together with a given error message. */
+fn render_position_setup(
+    input: &TextInput,
+    message: &Option<String>,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
+    set_active_console_texts(ctx);
+    ctx.print_color(
+        TEXT_LEFT_START,
+        1,
+        theme.text,
+        theme.background,
+        "SETUP POSITION",
+    );
+    ctx.print_color(
+        TEXT_LEFT_START,
+        MENU_ITEMS_TOP,
+        theme.text,
+        theme.background,
+        "Enter the FEN of the position:",
+    );
+    let mut row = MENU_ITEMS_TOP + 1;
+    for line in wrap_text(&format!("{}_", input.text), PANEL_WIDTH) {
+        ctx.print_color(
+            TEXT_LEFT_START,
+            row,
+            theme.input_text,
+            theme.background,
+            line,
+        );
+        row += 1;
+    }
+    if let Some(message) = message {
+        for line in wrap_text(message, PANEL_WIDTH) {
+            row += 1;
+            ctx.print_color(TEXT_LEFT_START, row, theme.check, theme.background, line);
+        }
+    }
+    ctx.print_color(
+        TEXT_LEFT_START,
+        row + 2,
+        theme.secondary_text,
+        theme.background,
+        "Enter: accept  Del: clear",
+    );
+    ctx.print_color(
+        TEXT_LEFT_START,
+        row + 3,
+        theme.secondary_text,
+        theme.background,
+        "Esc: cancel",
+    );
+}
+
+fn render_replay_status(replay: &Replay, move_count: usize, theme: &Theme, ctx: &mut BTerm) {
+    if let Some(ply) = replay.ply() {
+        set_active_console_texts(ctx);
+        let mut status = format!("REPLAY {}/{}", ply, move_count);
+        if replay.is_auto_play() {
+            status.push_str(&format!("  AUTO {:.1}s", replay.interval_secs()));
+        }
+        ctx.print_color(
+            TEXT_LEFT_START,
+            TEXT_HEIGHT - 1,
+            theme.replay_move,
+            theme.background,
+            status,
+        );
+    }
+}
+
+/** Render the typed text of a given command line together with the matching moves or a message. */
+pub(super) fn render_command_line(command_line: &CommandLine, theme: &Theme, ctx: &mut BTerm) {
+    set_active_console_texts(ctx);
+    let info = match &command_line.message {
+        Some(message) => message.clone(),
+        None => command_line
+            .matches()
+            .iter()
+            .map(|candidate| candidate.san.as_str())
+            .collect::<Vec<&str>>()
+            .join(" "),
+    };
+    let info: String = info.chars().take(PANEL_WIDTH as usize).collect();
+    ctx.print_color(
+        TEXT_LEFT_START,
+        TEXT_HEIGHT - 2,
+        theme.text,
+        theme.background,
+        info,
+    );
+    ctx.print_color(
+        TEXT_LEFT_START,
+        TEXT_HEIGHT - 1,
+        theme.input_text,
+        theme.background,
+        format!("> {}_", command_line.input.text),
+    );
+}
+
+/** Render the keyboard cursor around the square at a given board coordinate. */
+pub(super) fn render_board_cursor(
+    coord: (i8, i8),
+    view: &BoardView,
+    theme: &Theme,
+    ctx: &mut BTerm,
+) {
+    set_active_console_texts(ctx);
+    let (x, y) = view.screen_coord(coord);
+    ctx.draw_hollow_box(
+        x as i32 * FONT_WIDTH_FACTOR,
+        y as i32 * FONT_HEIGHT_FACTOR,
+        FONT_WIDTH_FACTOR - 1,
+        FONT_HEIGHT_FACTOR - 1,
+        theme.cursor,
+        theme.background,
+    );
+}
+
 impl GameState for MainState {
     fn tick(&mut self, ctx: &mut BTerm) {
-        render_board(&self.game.board, ctx);
-        render_pieces(&self.game.pieces, ctx);
-        render_game_end_and_check(&self.game, ctx);
-        render_promotion_pawn(self.game.promotion_pawn(), ctx);
+        self.update_players(ctx.frame_time_ms);
+        let move_count = self.game.executed_moves().len();
+        self.replay.advance(ctx.frame_time_ms, move_count);
+        if self.quit {
+            ctx.quit();
+        }
+        let game = match self.setup_position() {
+            Some(game) => Rc::new(game),
+            None => self.displayed_game(),
+        };
+        if !self.is_paused() {
+            self.analysis.update(&game, self.book.as_ref());
+        }
+        let view = self.view(&game);
+        let theme = self.themes.selected().clone();
+        apply_theme_fonts(&self.themes, ctx);
+        set_active_console_texts(ctx);
+        ctx.cls();
+        render_board(&game.board, &view, &theme, ctx);
         match &self.app_state {
-            AppState::AwaitingMoveSelection { user_move } => {
-                render_selected_piece(&user_move.piece, ctx)
+            AppState::DraggingPiece { user_move } => {
+                let pieces = game
+                    .pieces
+                    .iter()
+                    .filter(|piece| piece.square != user_move.piece.square)
+                    .copied()
+                    .collect();
+                render_pieces(&pieces, &view, &theme, ctx);
+                render_dragged_piece(&user_move.piece, ctx.mouse_point(), &theme, ctx);
             }
-            AppState::AwaitingPieceSelection => {}
+            _ => render_pieces(&game.pieces, &view, &theme, ctx),
+        }
+        render_last_move(&game, &view, &theme, ctx);
+        render_game_end_and_check(&game, &view, &theme, ctx);
+        render_promotion_pawn(game.promotion_pawn(), &view, &theme, ctx);
+        if let Some(user_move) = self.user_move() {
+            render_selected_piece(&user_move.piece, &view, &theme, ctx)
+        }
+        let annotation = &self.game.move_tree().node(self.displayed_node()).annotation;
+        render_markers(annotation, &view, &theme, ctx);
+        if let Some(hint) = self.analysis.hint().filter(|_| !self.is_paused()) {
+            render_hint(hint, &view, &theme, ctx);
+        }
+        render_coordinates(&game.board, &view, &theme, ctx);
+        let bottom_color = self.bottom_color(&game);
+        render_captured_pieces(&game, bottom_color, &theme, ctx);
+        let ply_offset = self.game.initial_ply_offset();
+        match &self.app_state {
+            AppState::InMenu { menu } => render_menu(menu, &self.settings, &theme, ctx),
+            AppState::SettingUpPosition { input, message } => {
+                render_position_setup(input, message, &theme, ctx)
+            }
+            _ => {
+                if let Some(evaluation) = self.analysis.evaluation() {
+                    render_evaluation_bar(evaluation.total(), bottom_color, &theme, ctx);
+                }
+                match &self.app_state {
+                    AppState::EditingComment { input, .. } => {
+                        render_comment_input(input, &theme, ctx)
+                    }
+                    _ => render_comment(annotation, &theme, ctx),
+                }
+                self.move_list
+                    .follow(self.replay.ply().unwrap_or(move_count) + ply_offset);
+                let replay_ply = self.replay.ply();
+                if self.shows_evaluation {
+                    render_evaluation_terms(self.analysis.evaluation(), &theme, ctx);
+                } else {
+                    render_executed_moves(&self.game, &mut self.move_list, replay_ply, &theme, ctx);
+                    if let Some(book_position) = self.analysis.book_position() {
+                        render_book_position(book_position, &theme, ctx);
+                    }
+                }
+                match &self.app_state {
+                    AppState::EnteringMove { command_line } => {
+                        render_command_line(command_line, &theme, ctx)
+                    }
+                    _ => {
+                        render_variations(&self.game, self.displayed_node(), &theme, ctx);
+                        render_replay_status(&self.replay, move_count, &theme, ctx);
+                    }
+                }
+                if let Some(status) = self.status.text() {
+                    render_status_message(status, &theme, ctx);
+                } else if self.computer.is_thinking() {
+                    render_thinking(&theme, ctx);
+                } else if let Some(message) = &self.network_message {
+                    render_network_message(message, &theme, ctx);
+                } else if let Some(opening) = self.analysis.opening().filter(|_| {
+                    self.game_end.is_none() && !game.is_check_mate() && !game.is_stalemate()
+                }) {
+                    render_opening(opening, &theme, ctx);
+                }
+            }
+        }
+        if let Some(clock) = &self.clock {
+            render_clock(clock, self.game.next_move_color(), &theme, ctx);
+        }
+        if let Some(game_end) = self.game_end {
+            render_game_end(game_end, &theme, ctx);
+        }
+        if let Some(cursor) = self.board_cursor {
+            render_board_cursor(cursor, &view, &theme, ctx);
         }
-        render_executed_moves(&self.game, ctx);
+        let line_length = displayed_line(&self.game).len();
 
+        set_active_console_texts(ctx);
+        let text_point = ctx.mouse_point();
         set_active_console_pieces(ctx);
         INPUT.lock().for_each_message(|message| {
             let mouse_point = ctx.mouse_point();
-            let coord = (mouse_point.x as i8, mouse_point.y as i8);
+            let coord = view.board_coord((mouse_point.x as i8, mouse_point.y as i8));
             match message {
-                BEvent::MouseButtonDown { button: 0 } => self.evaluate_mouse_click(coord),
+                BEvent::MouseButtonDown { button: 0 } if self.is_paused() => {
+                    if let AppState::InMenu { menu } = &self.app_state {
+                        if let Some(index) = menu_click_at((text_point.x, text_point.y), menu) {
+                            self.evaluate_menu_click(index);
+                        }
+                    }
+                }
+                BEvent::MouseButtonDown { button: 0 } => {
+                    let text_coord = (text_point.x, text_point.y);
+                    let click = match self.shows_evaluation {
+                        true => None,
+                        false => {
+                            move_list_click_at(text_coord, &self.move_list, line_length, ply_offset)
+                        }
+                    };
+                    match click {
+                        Some(MoveListClick::Move(ply)) => self.evaluate_move_list_click(ply),
+                        Some(MoveListClick::PageUp) => self.move_list.page_up(),
+                        Some(MoveListClick::PageDown) => {
+                            self.move_list.page_down(line_length + ply_offset)
+                        }
+                        None => self.evaluate_mouse_press(coord),
+                    }
+                }
+                BEvent::MouseButtonUp { button: 0 } => self.evaluate_mouse_release(coord),
+                BEvent::MouseButtonDown { button: 1 } => self.evaluate_marker_start(coord),
+                BEvent::MouseButtonUp { button: 1 } => {
+                    let color = match ctx.shift {
+                        true => MarkerColor::Red,
+                        false => MarkerColor::Green,
+                    };
+                    self.evaluate_marker_end(coord, color)
+                }
+                BEvent::KeyboardInput {
+                    key, pressed: true, ..
+                } => self.evaluate_key(key),
+                BEvent::Character { c } => self.evaluate_character(c),
                 BEvent::CloseRequested { .. } => ctx.quit(),
                 _ => {}
             }
         });
 
-        if let AppState::AwaitingMoveSelection { user_move } = &self.app_state {
-            render_possible_moves(user_move.possible_moves.clone(), ctx);
+        if let Some(user_move) = self.user_move() {
+            render_possible_moves(user_move.possible_moves.clone(), &view, &theme, ctx);
         }
     }
 }
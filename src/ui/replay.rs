@@ -0,0 +1,105 @@
+const DEFAULT_INTERVAL_MS: f32 = 1000.0;
+const MIN_INTERVAL_MS: f32 = 125.0;
+const MAX_INTERVAL_MS: f32 = 8000.0;
+
+/** Navigation through the executed moves of a game without changing the game itself. The replay
+either shows the live position of the game or the position after a given number of moves (plies). */
+#[derive(Debug)]
+pub struct Replay {
+    ply: Option<usize>,
+    auto_play: bool,
+    interval_ms: f32,
+    elapsed_ms: f32,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self {
+            ply: None,
+            auto_play: false,
+            interval_ms: DEFAULT_INTERVAL_MS,
+            elapsed_ms: 0.0,
+        }
+    }
+
+    /** The number of moves of the shown position. None if the live position is shown. */
+    pub fn ply(&self) -> Option<usize> {
+        self.ply
+    }
+    /** Whether a historical position is shown instead of the live position. */
+    pub fn is_active(&self) -> bool {
+        self.ply.is_some()
+    }
+    /** Whether the moves are currently replayed automatically. */
+    pub fn is_auto_play(&self) -> bool {
+        self.auto_play
+    }
+    /** The time in seconds each position is shown while playing automatically. */
+    pub fn interval_secs(&self) -> f32 {
+        self.interval_ms / 1000.0
+    }
+
+    /** Show the start position of a game with a given number of moves. */
+    pub fn first(&mut self, move_count: usize) {
+        self.jump_to(0, move_count);
+    }
+    /** Show the position before the currently shown position. */
+    pub fn previous(&mut self, move_count: usize) {
+        let current = self.ply.unwrap_or(move_count);
+        self.jump_to(current.saturating_sub(1), move_count);
+    }
+    /** Show the position after the currently shown position. */
+    pub fn next(&mut self, move_count: usize) {
+        if let Some(current) = self.ply {
+            self.jump_to(current + 1, move_count);
+        }
+    }
+    /** Show the live position again. */
+    pub fn last(&mut self) {
+        self.ply = None;
+        self.auto_play = false;
+    }
+    /** Show the position after a given number of moves. The live position is shown if the given
+    number is not less than the number of moves of the game. */
+    pub fn jump_to(&mut self, ply: usize, move_count: usize) {
+        if ply < move_count {
+            self.ply = Some(ply);
+        } else {
+            self.last();
+        }
+        self.elapsed_ms = 0.0;
+    }
+
+    /** Start or stop playing the moves automatically. Starts at the first position if the live
+    position is currently shown. */
+    pub fn toggle_auto_play(&mut self, move_count: usize) {
+        if self.auto_play {
+            self.auto_play = false;
+            return;
+        }
+        if self.ply.is_none() {
+            self.first(move_count);
+        }
+        self.auto_play = self.ply.is_some();
+        self.elapsed_ms = 0.0;
+    }
+    /** Show each position for a shorter time while playing automatically. */
+    pub fn faster(&mut self) {
+        self.interval_ms = (self.interval_ms / 2.0).max(MIN_INTERVAL_MS);
+    }
+    /** Show each position for a longer time while playing automatically. */
+    pub fn slower(&mut self) {
+        self.interval_ms = (self.interval_ms * 2.0).min(MAX_INTERVAL_MS);
+    }
+
+    /** Advance the automatic play by a given amount of time. */
+    pub fn advance(&mut self, frame_time_ms: f32, move_count: usize) {
+        if !self.auto_play {
+            return;
+        }
+        self.elapsed_ms += frame_time_ms;
+        if self.elapsed_ms >= self.interval_ms {
+            self.next(move_count);
+        }
+    }
+}
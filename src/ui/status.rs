@@ -0,0 +1,60 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+const DISPLAY_MS: f32 = 4000.0;
+
+/** A short message about the result of an action of the user, e.g. that the game was saved, which
+is shown for a few seconds. Actions done in the background report their result through a sender of
+the message. */
+#[derive(Debug)]
+pub struct StatusMessage {
+    text: Option<String>,
+    remaining_ms: f32,
+    sender: Sender<String>,
+    receiver: Receiver<String>,
+}
+
+impl Default for StatusMessage {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            text: None,
+            remaining_ms: 0.0,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl StatusMessage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** The shown message, if any. */
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /** Show a given message, replacing the one shown before. */
+    pub fn show(&mut self, text: impl Into<String>) {
+        self.text = Some(text.into());
+        self.remaining_ms = DISPLAY_MS;
+    }
+
+    /** A sender with which another thread shows a message the next time this message advances. */
+    pub fn sender(&self) -> Sender<String> {
+        self.sender.clone()
+    }
+
+    /** Let a given number of milliseconds pass, which hides the message after a while. The last
+    message sent by another thread is shown from then on. */
+    pub fn advance(&mut self, elapsed_ms: f32) {
+        self.remaining_ms -= elapsed_ms;
+        if self.remaining_ms <= 0.0 {
+            self.text = None;
+        }
+        if let Some(text) = self.receiver.try_iter().last() {
+            self.show(text);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/** A single line of text entered by the user with the keyboard. */
+#[derive(Debug, Clone)]
+pub struct TextInput {
+    pub text: String,
+}
+
+impl TextInput {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+        }
+    }
+
+    /** Append a given typed character. Control characters are ignored. */
+    pub fn push(&mut self, c: char) {
+        if !c.is_control() {
+            self.text.push(c);
+        }
+    }
+    /** Remove the last character. */
+    pub fn backspace(&mut self) {
+        self.text.pop();
+    }
+}
@@ -0,0 +1,337 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use bracket_lib::prelude::*;
+use toml::Table;
+
+use crate::domain::chessboard::SquareColor;
+use crate::domain::pieces::PieceColor;
+use crate::export::DiagramColors;
+
+/** The file of the resource directory in which additional themes are defined. */
+pub const THEMES_FILE: &str = "themes.toml";
+
+const DEFAULT_PIECES_FILE: &str = "chess_pieces.png";
+const DEFAULT_FONT_FILE: &str = "terminal8x8.png";
+
+/** A font used by the consoles: A sprite sheet file within the resource directory together with
+the size of a single glyph in pixels. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeFont {
+    pub file: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ThemeFont {
+    /** The path of the file of this font within a given resource directory. The default font is
+    embedded into the application, so its plain file name is kept. */
+    pub fn path(&self, resource_dir: &str) -> String {
+        if self.file == DEFAULT_FONT_FILE {
+            return self.file.clone();
+        }
+        let path = Path::new(resource_dir).join(&self.file);
+        let path = match path.is_absolute() {
+            true => path,
+            false => env::current_dir().unwrap_or_default().join(path),
+        };
+        path.to_string_lossy().to_string()
+    }
+}
+
+/** The colors and sprite sheets with which the board, the pieces and the texts are rendered. */
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub light_square: (u8, u8, u8),
+    pub dark_square: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+    pub white_pieces: (u8, u8, u8),
+    pub black_pieces: (u8, u8, u8),
+    pub possible_move: (u8, u8, u8),
+    pub last_move: (u8, u8, u8),
+    pub check: (u8, u8, u8),
+    pub promotion: (u8, u8, u8),
+    pub selection: (u8, u8, u8),
+    pub cursor: (u8, u8, u8),
+    pub text: (u8, u8, u8),
+    pub secondary_text: (u8, u8, u8),
+    pub input_text: (u8, u8, u8),
+    pub replay_move: (u8, u8, u8),
+    pub pieces_font: ThemeFont,
+    pub text_font: ThemeFont,
+}
+
+impl Theme {
+    /** The theme with gray squares the application always had. */
+    pub fn classic() -> Self {
+        Self {
+            name: "classic".to_string(),
+            light_square: LIGHT_GRAY,
+            dark_square: DARK_GRAY,
+            background: LIGHT_GREEN,
+            white_pieces: WHITE,
+            black_pieces: BLACK,
+            possible_move: LIGHT_GREEN,
+            last_move: GOLD,
+            check: RED,
+            promotion: LIGHT_SALMON,
+            selection: LIGHT_CYAN,
+            cursor: MAGENTA,
+            text: WHITE,
+            secondary_text: GRAY,
+            input_text: YELLOW,
+            replay_move: BLUE,
+            pieces_font: ThemeFont {
+                file: DEFAULT_PIECES_FILE.to_string(),
+                width: 64,
+                height: 64,
+            },
+            text_font: ThemeFont {
+                file: DEFAULT_FONT_FILE.to_string(),
+                width: 8,
+                height: 8,
+            },
+        }
+    }
+    /** A theme with the brown squares of a wooden board. */
+    pub fn wood() -> Self {
+        Self {
+            name: "wood".to_string(),
+            light_square: (240, 217, 181),
+            dark_square: (181, 136, 99),
+            background: (49, 46, 43),
+            possible_move: (130, 151, 105),
+            last_move: (205, 210, 106),
+            ..Self::classic()
+        }
+    }
+    /** A theme with the blue squares of a tournament board. */
+    pub fn ocean() -> Self {
+        Self {
+            name: "ocean".to_string(),
+            light_square: (222, 227, 230),
+            dark_square: (140, 162, 173),
+            background: (38, 50, 56),
+            possible_move: (100, 180, 220),
+            last_move: (255, 200, 80),
+            selection: (180, 230, 255),
+            ..Self::classic()
+        }
+    }
+    /** A theme with strongly contrasting colors. */
+    pub fn contrast() -> Self {
+        Self {
+            name: "contrast".to_string(),
+            light_square: WHITE,
+            dark_square: (0, 100, 0),
+            background: BLACK,
+            white_pieces: (255, 220, 0),
+            black_pieces: (128, 0, 128),
+            possible_move: CYAN,
+            last_move: ORANGE,
+            ..Self::classic()
+        }
+    }
+
+    /** The color of a square of a given color. */
+    pub fn square_color(&self, square_color: SquareColor) -> (u8, u8, u8) {
+        match square_color {
+            SquareColor::White => self.light_square,
+            SquareColor::Black => self.dark_square,
+        }
+    }
+    /** The color of a coordinate label rendered onto a square of a given color. */
+    pub fn label_color(&self, square_color: SquareColor) -> (u8, u8, u8) {
+        match square_color {
+            SquareColor::White => self.dark_square,
+            SquareColor::Black => self.light_square,
+        }
+    }
+    /** The color of the pieces of a given color. */
+    pub fn piece_color(&self, piece_color: PieceColor) -> (u8, u8, u8) {
+        match piece_color {
+            PieceColor::White => self.white_pieces,
+            PieceColor::Black => self.black_pieces,
+        }
+    }
+    /** The colors of an exported diagram of the board of this theme. The diagram keeps its white
+    background, so it can be printed. */
+    pub fn diagram_colors(&self) -> DiagramColors {
+        DiagramColors {
+            light_square: self.light_square,
+            dark_square: self.dark_square,
+            white_pieces: self.white_pieces,
+            black_pieces: self.black_pieces,
+            last_move: self.last_move,
+            ..DiagramColors::default()
+        }
+    }
+
+    /** A new theme with a given name whose values are defined by a given table of a themes file.
+    Missing values are taken from a given base theme. */
+    fn from_table(name: &str, table: &Table, base: &Theme) -> Result<Self, String> {
+        let mut theme = Theme {
+            name: name.to_string(),
+            ..base.clone()
+        };
+        for (key, value) in table {
+            let error =
+                |expected: &str| format!("Theme '{}', {}: Expected {}", name, key, expected);
+            if let Some(color) = theme.color_mut(key) {
+                *color = value
+                    .as_str()
+                    .and_then(parse_color)
+                    .ok_or_else(|| error("a color like \"#RRGGBB\""))?;
+                continue;
+            }
+            let text = || value.as_str().map(|text| text.to_string());
+            let size = || value.as_integer().and_then(|size| u32::try_from(size).ok());
+            match key.as_str() {
+                "base" => {}
+                "pieces_file" => theme.pieces_font.file = text().ok_or_else(|| error("a file"))?,
+                "pieces_tile_size" => {
+                    let tile_size = size().ok_or_else(|| error("a size in pixels"))?;
+                    theme.pieces_font.width = tile_size;
+                    theme.pieces_font.height = tile_size;
+                }
+                "font_file" => theme.text_font.file = text().ok_or_else(|| error("a file"))?,
+                "font_width" => theme.text_font.width = size().ok_or_else(|| error("a width"))?,
+                "font_height" => {
+                    theme.text_font.height = size().ok_or_else(|| error("a height"))?
+                }
+                _ => return Err(format!("Theme '{}': Unknown key '{}'", name, key)),
+            }
+        }
+        Ok(theme)
+    }
+    /** The color of this theme with a given key of a themes file. */
+    fn color_mut(&mut self, key: &str) -> Option<&mut (u8, u8, u8)> {
+        match key {
+            "light_square" => Some(&mut self.light_square),
+            "dark_square" => Some(&mut self.dark_square),
+            "background" => Some(&mut self.background),
+            "white_pieces" => Some(&mut self.white_pieces),
+            "black_pieces" => Some(&mut self.black_pieces),
+            "possible_move" => Some(&mut self.possible_move),
+            "last_move" => Some(&mut self.last_move),
+            "check" => Some(&mut self.check),
+            "promotion" => Some(&mut self.promotion),
+            "selection" => Some(&mut self.selection),
+            "cursor" => Some(&mut self.cursor),
+            "text" => Some(&mut self.text),
+            "secondary_text" => Some(&mut self.secondary_text),
+            "input_text" => Some(&mut self.input_text),
+            "replay_move" => Some(&mut self.replay_move),
+            _ => None,
+        }
+    }
+}
+
+/** Parse a color in the hexadecimal form "#RRGGBB". */
+fn parse_color(text: &str) -> Option<(u8, u8, u8)> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some((component(0)?, component(2)?, component(4)?))
+}
+
+/** All available themes of which one is selected for rendering. */
+#[derive(Debug)]
+pub struct Themes {
+    themes: Vec<Theme>,
+    selected: usize,
+}
+
+impl Themes {
+    /** The built-in themes only, the first one being selected. */
+    pub fn built_in() -> Self {
+        Self {
+            themes: vec![
+                Theme::classic(),
+                Theme::wood(),
+                Theme::ocean(),
+                Theme::contrast(),
+            ],
+            selected: 0,
+        }
+    }
+    /** The built-in themes followed by the themes defined in the themes file of a given resource
+    directory. A missing file is no error. */
+    pub fn load(resource_dir: &str) -> Result<Self, String> {
+        let mut themes = Self::built_in();
+        let path = format!("{}/{}", resource_dir, THEMES_FILE);
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Ok(themes);
+        };
+        let document: Table = text
+            .parse()
+            .map_err(|error| format!("{}: {}", path, error))?;
+        for (name, value) in &document {
+            let table = value
+                .as_table()
+                .ok_or_else(|| format!("{}: '{}' is not a table of a theme", path, name))?;
+            let base = match table.get("base") {
+                Some(base_name) => {
+                    let base_name = base_name.as_str().unwrap_or_default();
+                    themes
+                        .find(base_name)
+                        .ok_or_else(|| format!("Theme '{}': Unknown base '{}'", name, base_name))?
+                }
+                None => &themes.themes[0],
+            };
+            let theme = Theme::from_table(name, table, base)?;
+            for font in [&theme.pieces_font, &theme.text_font] {
+                if !Path::new(&font.path(resource_dir)).is_file() && font.file != DEFAULT_FONT_FILE
+                {
+                    return Err(format!("Theme '{}': Missing file '{}'", name, font.file));
+                }
+            }
+            match themes.themes.iter().position(|other| other.name == *name) {
+                Some(index) => themes.themes[index] = theme,
+                None => themes.themes.push(theme),
+            }
+        }
+        Ok(themes)
+    }
+
+    /** The theme with a given name. */
+    pub fn find(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.name == name)
+    }
+    /** Select the theme with a given name. Returns whether there is such a theme. */
+    pub fn select(&mut self, name: &str) -> bool {
+        match self.themes.iter().position(|theme| theme.name == name) {
+            Some(index) => {
+                self.selected = index;
+                true
+            }
+            None => false,
+        }
+    }
+    /** The theme currently used for rendering. */
+    pub fn selected(&self) -> &Theme {
+        &self.themes[self.selected]
+    }
+    /** Select the theme following the currently selected one, starting over after the last. */
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.themes.len();
+    }
+
+    /** All distinct fonts used by any of the themes. */
+    pub fn fonts(&self) -> Vec<ThemeFont> {
+        let mut result: Vec<ThemeFont> = Vec::new();
+        self.themes
+            .iter()
+            .flat_map(|theme| [&theme.pieces_font, &theme.text_font])
+            .for_each(|font| {
+                if !result.contains(font) {
+                    result.push(font.clone());
+                }
+            });
+        result
+    }
+}